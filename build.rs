@@ -0,0 +1,106 @@
+//! Embeds the exercises as they were handed out, so that `rustlings reset`
+//! can restore them later on. In a git checkout that's how each exercise was
+//! first committed, whatever the learner did to it since. Elsewhere, like
+//! when installing from a published package, it's the exercises on disk.
+//! A shallow clone, like the one CI checks out, gets no originals at all: its
+//! only commit adds every exercise the way the learner left it.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            // Skip the build output of the clippy and build script exercises
+            if path.file_name().is_some_and(|name| name != "target") {
+                collect_sources(&path, sources);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// The content of every source below `exercises` in the commit that added it,
+// keyed by its path. `None` outside of a git checkout.
+fn first_committed() -> Option<BTreeMap<String, String>> {
+    let log = git(&[
+        "log",
+        "--reverse",
+        "--no-renames",
+        "--diff-filter=A",
+        "--relative",
+        "--name-only",
+        "--format=commit %H",
+        "--",
+        "exercises",
+    ])?;
+    let mut sources = BTreeMap::new();
+    let mut commit = "";
+    for line in log.lines().filter(|line| !line.is_empty()) {
+        if let Some(hash) = line.strip_prefix("commit ") {
+            commit = hash;
+        } else if line.ends_with(".rs") && !line.split('/').any(|part| part == "target") {
+            if sources.contains_key(line) {
+                // Added again after being deleted, the first version counts
+                continue;
+            }
+            let content = git(&["show", &format!("{commit}:./{line}")])?;
+            sources.insert(line.to_string(), content);
+        }
+    }
+    Some(sources)
+}
+
+fn main() {
+    // Deliberately not rerun when an exercise or `info.toml` changes: the
+    // embedded copies have to stay the pristine ones while the learner works
+    // on the exercises.
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let shallow = git(&["rev-parse", "--is-shallow-repository"]).is_some_and(|out| out.trim() == "true");
+    let mut pristine = if shallow {
+        BTreeMap::new()
+    } else {
+        first_committed().unwrap_or_default()
+    };
+    // Exercises that were never committed are taken as they are
+    let mut sources = Vec::new();
+    if !shallow {
+        collect_sources(Path::new("exercises"), &mut sources);
+    }
+    for path in sources {
+        let key = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        pristine
+            .entry(key)
+            .or_insert_with(|| fs::read_to_string(&path).unwrap());
+    }
+
+    let mut generated = String::from("pub static PRISTINE_EXERCISES: &[(&str, &str)] = &[\n");
+    for (key, content) in pristine {
+        generated.push_str(&format!("    ({key:?}, {content:?}),\n"));
+    }
+    generated.push_str("];\n");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("pristine_exercises.rs"), generated).unwrap();
+}
@@ -0,0 +1,173 @@
+use crate::exercise::{Exercise, ExerciseOutput, Mode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+pub const CACHE_DIR: &str = ".rustlings-cache";
+
+// Remembers the outcome of building and running each exercise, so that
+// exercises which haven't changed since their last run are not rebuilt.
+// Only the last outcome of every exercise is kept.
+#[derive(Clone, Debug)]
+pub struct BuildCache {
+    dir: PathBuf,
+}
+
+// The cached outcome of an exercise, stored as `<name>.json` in the cache dir
+#[derive(Serialize, Deserialize)]
+pub struct CachedOutcome {
+    // The key of the inputs this outcome was produced from
+    pub key: String,
+    // Whether the exercise compiled (and for clippy exercises, passed the lints)
+    pub compiled: bool,
+    // Whether running the compiled exercise succeeded
+    pub passed: bool,
+    pub output: ExerciseOutput,
+}
+
+impl CachedOutcome {
+    // The outcome of an exercise that compiled and then ran
+    pub fn ran(key: String, result: &Result<ExerciseOutput, ExerciseOutput>) -> Self {
+        let (passed, output) = match result {
+            Ok(output) => (true, output.clone()),
+            Err(output) => (false, output.clone()),
+        };
+        CachedOutcome {
+            key,
+            compiled: true,
+            passed,
+            output,
+        }
+    }
+
+    // The outcome of an exercise that failed to compile
+    pub fn failed_to_compile(key: String, output: &ExerciseOutput) -> Self {
+        CachedOutcome {
+            key,
+            compiled: false,
+            passed: false,
+            output: output.clone(),
+        }
+    }
+
+    // The result of running the exercise, if it compiled
+    pub fn into_run_result(self) -> Result<ExerciseOutput, ExerciseOutput> {
+        if self.passed {
+            Ok(self.output)
+        } else {
+            Err(self.output)
+        }
+    }
+}
+
+impl BuildCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        BuildCache { dir: dir.into() }
+    }
+
+    // The key of everything that influences the outcome of the exercise:
+    // its source, its mode, the build script of build script exercises, the
+    // other files it depends on including hidden tests, the dependencies of
+    // cargo exercises, the limits it runs with and the compiler version.
+    // `None` if the exercise can't be read.
+    pub fn key(&self, exercise: &Exercise) -> Option<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(fs::read(&exercise.path).ok()?);
+        hasher.update(format!("{:?}", exercise.mode));
+        if exercise.mode == Mode::BuildScript {
+            let build_script = exercise.path.parent()?.join("build.rs");
+            hasher.update(fs::read(build_script).ok()?);
+        }
+        for file in exercise.watch.iter().chain(&exercise.hidden_tests) {
+            hasher.update(fs::read(file).ok()?);
+        }
+        if exercise.mode == Mode::Cargo {
+            hasher.update(toml::to_string(&exercise.dependencies).ok()?);
+        }
+        // A pass within a generous timeout says nothing about a stricter one
+        hasher.update(format!("{:?}", exercise.limits()));
+        hasher.update(rustc_version());
+        Some(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+    }
+
+    // The outcome stored for the exercise, if it was produced from `key`
+    pub fn lookup(&self, exercise: &Exercise, key: &str) -> Option<CachedOutcome> {
+        let content = fs::read_to_string(self.outcome_path(exercise)).ok()?;
+        let outcome: CachedOutcome = serde_json::from_str(&content).ok()?;
+        (outcome.key == key).then_some(outcome)
+    }
+
+    // Replace the stored outcome of the exercise. Failing to write the cache
+    // only costs a rebuild next time, so errors are ignored. Timeouts say
+    // nothing about the exercise itself, so they are never stored.
+    pub fn store(&self, exercise: &Exercise, outcome: &CachedOutcome) {
+        if outcome.output.timed_out || fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        if let Ok(content) = serde_json::to_string(outcome) {
+            let _ignored = fs::write(self.outcome_path(exercise), content);
+        }
+    }
+
+    fn outcome_path(&self, exercise: &Exercise) -> PathBuf {
+        self.dir.join(format!("{}.json", exercise.file_name()))
+    }
+}
+
+// The output of `rustc -vV`, which includes the commit hash of the compiler
+fn rustc_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        Command::new("rustc")
+            .arg("-vV")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cicv::grade;
+    use crate::exercise::BuildDir;
+
+    #[test]
+    fn test_lookup_only_matching_key() {
+        let dir = std::env::temp_dir().join(format!("rustlings_cache_{}", std::process::id()));
+        let cache = BuildCache::new(&dir);
+        let mut exercise =
+            Exercise::new("compSuccess", "tests/fixture/success/compSuccess.rs", Mode::Compile);
+        exercise.cache = Some(cache.clone());
+        let key = cache.key(&exercise).unwrap();
+        assert!(cache.lookup(&exercise, &key).is_none());
+
+        let output = ExerciseOutput {
+            stdout: "cached".into(),
+            stderr: String::new(),
+            timed_out: false,
+        };
+        cache.store(&exercise, &CachedOutcome::ran(key.clone(), &Ok(output)));
+        let cached = cache.lookup(&exercise, &key).unwrap();
+        assert_eq!(cached.into_run_result().unwrap().stdout, "cached");
+        assert!(cache.lookup(&exercise, "another key").is_none());
+
+        // A cache hit skips compiling and running the exercise
+        let compiled = exercise.compile().unwrap();
+        assert_eq!(compiled.run().unwrap().stdout, "cached");
+        assert_eq!(grade(&exercise, &BuildDir::Shared).stdout, "cached");
+
+        // Other limits make for another key
+        let mut stricter = exercise.clone();
+        stricter.timeout = Some(1);
+        assert_ne!(cache.key(&stricter).unwrap(), key);
+        stricter.timeout = None;
+        stricter.limits.memory_mb = Some(64);
+        assert_ne!(cache.key(&stricter).unwrap(), key);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
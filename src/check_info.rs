@@ -0,0 +1,295 @@
+use crate::cicv::grade_isolated;
+use crate::exercise::{has_marker, normalize, Exercise, ExerciseList, Mode};
+use crate::reset;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+// Check `info.toml` against the exercises below `exercises_dir`, returning
+// every problem found. It expects none of the exercises to be solved yet,
+// which the exercises rustlings embedded for `rustlings reset` never are,
+// while the ones on disk may be.
+pub fn check_info(list: &ExerciseList, exercises_dir: &Path, jobs: usize) -> Vec<String> {
+    let mut problems = vec![];
+    let mut names = HashSet::new();
+    let mut paths = HashMap::new();
+    let mut readable = vec![];
+
+    for exercise in &list.exercises {
+        if !names.insert(&exercise.name) {
+            problems.push(format!("{}: listed more than once", exercise.name));
+        }
+        if let Some(first) = paths.insert(normalize(&exercise.path), exercise) {
+            problems.push(format!("{}: has the same path as `{}`", exercise.name, first.name));
+        }
+        let source = match fs::read_to_string(&exercise.path) {
+            Ok(source) => source,
+            Err(e) => {
+                problems.push(format!("{}: can't read {}: {e}", exercise.name, exercise));
+                continue;
+            }
+        };
+        // Learners remove the marker and add tests, so look at the original
+        problems.extend(check_source(exercise, reset::pristine(&exercise.path).unwrap_or(&source)));
+        for file in exercise.watch.iter().filter(|file| !file.exists()) {
+            problems.push(format!("{}: watches {}, which doesn't exist", exercise.name, file.display()));
+        }
+        let extra = [("hidden tests file", &exercise.hidden_tests), ("solution", &exercise.solution)];
+        for (what, file) in extra {
+            if let Some(file) = file.as_ref().filter(|file| !file.exists()) {
+                problems.push(format!("{}: the {what} {} doesn't exist", exercise.name, file.display()));
+            }
+        }
+        readable.push(exercise);
+    }
+
+    problems.extend(unlisted(exercises_dir, &paths.into_keys().collect()));
+    problems.extend(list.validate_metadata());
+    problems.extend(wrongly_graded(&readable, jobs));
+    problems
+}
+
+// Problems that show in the source of the exercise alone
+fn check_source(exercise: &Exercise, source: &str) -> Vec<String> {
+    let mut problems = vec![];
+    if !has_marker(source) {
+        problems.push(format!("{}: missing the `I AM NOT DONE` comment", exercise.name));
+    }
+    match exercise.mode {
+        Mode::Test if !has_tests(exercise, source) => problems.push(format!(
+            "{}: has mode \"test\" but contains no tests",
+            exercise.name
+        )),
+        Mode::Compile | Mode::Clippy if !source.contains("fn main") => problems.push(format!(
+            "{}: has mode \"{}\" but no `main` function",
+            exercise.name,
+            exercise.mode
+        )),
+        Mode::BuildScript if !exercise.path.with_file_name("build.rs").exists() => problems.push(
+            format!("{}: has mode \"buildscript\" but no build.rs next to it", exercise.name),
+        ),
+        Mode::Cargo if !has_tests(exercise, source) => problems.push(format!(
+            "{}: has mode \"cargo\" but contains no tests",
+            exercise.name
+        )),
+        _ => {}
+    }
+    if exercise.hidden_tests.is_some() {
+        if !matches!(exercise.mode, Mode::Test | Mode::Cargo) {
+            problems.push(format!(
+                "{}: only exercises with mode \"test\" or \"cargo\" can have hidden tests",
+                exercise.name
+            ));
+        }
+        // The exercise gets included into the file that adds the hidden tests,
+        // where inner attributes aren't allowed
+        if source.lines().any(|line| line.trim_start().starts_with("#![")) {
+            problems.push(format!(
+                "{}: has hidden tests, so it can't have inner attributes like `#![allow(...)]`",
+                exercise.name
+            ));
+        }
+    }
+    if exercise.mode != Mode::Cargo && !exercise.dependencies.is_empty() {
+        problems.push(format!(
+            "{}: only exercises with mode \"cargo\" can have dependencies",
+            exercise.name
+        ));
+    }
+    // Exact versions keep the vendored dependencies in sync with info.toml
+    for (name, dependency) in &exercise.dependencies {
+        let version = match dependency {
+            toml::Value::Table(table) => table.get("version"),
+            version => Some(version),
+        };
+        if !version.and_then(|v| v.as_str()).is_some_and(|v| v.starts_with('=')) {
+            problems.push(format!(
+                "{}: the version of `{name}` must be pinned like \"=1.2.3\"",
+                exercise.name
+            ));
+        }
+    }
+    problems
+}
+
+// The sources below `exercises_dir` that no exercise points to. Build scripts,
+// modules and the build output of cargo based exercises are not exercises.
+fn unlisted(exercises_dir: &Path, listed: &BTreeSet<PathBuf>) -> Vec<String> {
+    let pattern = exercises_dir.join("**").join("*.rs");
+    let Ok(sources) = glob::glob(&pattern.to_string_lossy()) else {
+        return vec![format!("can't search {} for exercises", exercises_dir.display())];
+    };
+    sources
+        .flatten()
+        .filter(|path| !path.components().any(|c| c.as_os_str() == "target"))
+        .filter(|path| !path.ends_with("build.rs") && !path.ends_with("mod.rs"))
+        .filter(|path| !listed.contains(&normalize(path)))
+        .map(|path| format!("{}: not listed in info.toml", path.display()))
+        .collect()
+}
+
+// Whether the exercise is tested, by its own tests or by hidden ones
+fn has_tests(exercise: &Exercise, source: &str) -> bool {
+    source.contains("#[test]") || exercise.hidden_tests.is_some()
+}
+
+// Grade the original exercises and their reference solutions, reporting the
+// exercises that pass although nobody solved them yet and the solutions that
+// don't pass
+fn wrongly_graded(exercises: &[&Exercise], jobs: usize) -> Vec<String> {
+    let originals_dir =
+        std::env::temp_dir().join(format!("rustlings_originals_{}", std::process::id()));
+    let originals: Vec<Option<Exercise>> = exercises
+        .iter()
+        .enumerate()
+        .map(|(i, exercise)| {
+            // Failing to write the original leaves grading the exercise on disk
+            original(exercise, reset::pristine, &originals_dir.join(i.to_string())).unwrap_or(None)
+        })
+        .collect();
+    let solutions: Vec<Exercise> = exercises.iter().filter_map(|e| e.solved()).collect();
+    let all: Vec<&Exercise> = exercises
+        .iter()
+        .zip(&originals)
+        .map(|(exercise, original)| original.as_ref().unwrap_or(exercise))
+        .chain(&solutions)
+        .collect();
+    let passed = passing(&all, jobs);
+    let _ignored = fs::remove_dir_all(&originals_dir);
+    let (unsolved, solved) = passed.split_at(exercises.len());
+
+    let mut problems: Vec<String> = exercises
+        .iter()
+        .zip(unsolved)
+        .filter(|(_, passed)| **passed)
+        .map(|(exercise, _)| format!("{}: passes without being solved", exercise.name))
+        .collect();
+    problems.extend(
+        solutions
+            .iter()
+            .zip(solved)
+            .filter(|(_, passed)| !**passed)
+            .map(|(solution, _)| {
+                format!("{}: the solution {} doesn't pass", solution.name, solution.path.display())
+            }),
+    );
+    problems
+}
+
+// The exercise as `pristine` has it, written into `dir` along with the build
+// script of build script exercises. `None` if the exercise on disk is the
+// original one or there is no original of it.
+fn original(
+    exercise: &Exercise,
+    pristine: impl Fn(&Path) -> Option<&'static str>,
+    dir: &Path,
+) -> io::Result<Option<Exercise>> {
+    let Some(content) = pristine(&exercise.path) else {
+        return Ok(None);
+    };
+    let build_script = exercise.path.with_file_name("build.rs");
+    let edited = |path: &Path| {
+        pristine(path).is_some_and(|original| fs::read_to_string(path).ok().as_deref() != Some(original))
+    };
+    let build_script_edited = exercise.mode == Mode::BuildScript && edited(&build_script);
+    if !edited(&exercise.path) && !build_script_edited {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(exercise.path.file_name().unwrap_or_default());
+    fs::write(&path, content)?;
+    if exercise.mode == Mode::BuildScript {
+        match pristine(&build_script) {
+            Some(original) => fs::write(dir.join("build.rs"), original)?,
+            None => fs::copy(&build_script, dir.join("build.rs")).map(|_| ())?,
+        }
+    }
+    Ok(Some(Exercise {
+        path,
+        cache: None,
+        ..exercise.clone()
+    }))
+}
+
+// Grade the exercises on `jobs` threads, returning whether each of them passed
+fn passing(exercises: &[&Exercise], jobs: usize) -> Vec<bool> {
+    let scratch_root = std::env::temp_dir().join(format!("rustlings_check_{}", std::process::id()));
+    let next = AtomicUsize::new(0);
+    let passed: Vec<AtomicBool> = exercises.iter().map(|_| AtomicBool::new(false)).collect();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, exercises.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(exercise) = exercises.get(i) else {
+                    break;
+                };
+                // Exercises may share a name here, so they don't share a scratch dir
+                if grade_isolated(exercise, &scratch_root.join(i.to_string())).result {
+                    passed[i].store(true, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+    let _ignored = fs::remove_dir_all(&scratch_root);
+
+    passed.into_iter().map(AtomicBool::into_inner).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercise(mode: Mode) -> Exercise {
+        Exercise::new("example", "exercises/topic/example.rs", mode)
+    }
+
+    #[test]
+    fn test_original() {
+        let dir =
+            std::env::temp_dir().join(format!("rustlings_test_original_{}", std::process::id()));
+        let exercise =
+            Exercise::new("compSuccess", "tests/fixture/success/compSuccess.rs", Mode::Compile);
+        let unchanged = |_: &Path| Some(include_str!("../tests/fixture/success/compSuccess.rs"));
+        assert!(original(&exercise, unchanged, &dir).unwrap().is_none());
+        assert!(original(&exercise, |_| None, &dir).unwrap().is_none());
+
+        let original = original(&exercise, |_| Some("// I AM NOT DONE\n"), &dir).unwrap().unwrap();
+        assert_eq!(original.path, dir.join("compSuccess.rs"));
+        assert_eq!(fs::read_to_string(&original.path).unwrap(), "// I AM NOT DONE\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_source() {
+        let source = "// I AM NOT DONE\n#[test]\nfn works() {}\n";
+        assert!(check_source(&exercise(Mode::Test), source).is_empty());
+        assert_eq!(
+            check_source(&exercise(Mode::Compile), source),
+            vec!["example: has mode \"compile\" but no `main` function"]
+        );
+        assert_eq!(
+            check_source(&exercise(Mode::BuildScript), "fn main() {}"),
+            vec![
+                "example: missing the `I AM NOT DONE` comment",
+                "example: has mode \"buildscript\" but no build.rs next to it",
+            ]
+        );
+
+        let mut exercise = exercise(Mode::Cargo);
+        exercise.dependencies = toml::from_str(
+            r#"
+            pinned = "=1.0.0"
+            unpinned = { version = "1.0", features = ["derive"] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            check_source(&exercise, source),
+            vec!["example: the version of `unpinned` must be pinned like \"=1.2.3\""]
+        );
+    }
+}
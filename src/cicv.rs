@@ -0,0 +1,414 @@
+use crate::exercise::{BuildDir, Exercise, ExerciseOutput, Mode, TestResults, HIDDEN_TESTS_MODULE};
+use crate::reset;
+use console::strip_ansi_codes;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+// Version of the layout of `check_result.json`.
+// Bump it whenever a field is renamed, removed or changes its meaning,
+// so that consumers of the file can tell the layouts apart.
+pub const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Serialize)]
+pub struct ExerciseCheckList {
+    pub schema_version: u32,
+    pub exercises: Vec<ExerciseResult>,
+    pub user_name: Option<String>,
+    pub statistics: ExerciseStatistics,
+}
+
+#[derive(Serialize)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub topic: String,
+    // Kept next to `failure` for graders that only look at pass/fail
+    pub result: bool,
+    pub mode: Mode,
+    // Why the exercise did not pass, `None` when it passed
+    pub failure: Option<Failure>,
+    // Wall-clock time spent compiling and running the exercise
+    pub duration_ms: u64,
+    // The output of the compiler and of the exercise, without terminal colors
+    pub stdout: String,
+    pub stderr: String,
+    // Individual tests of test harness and build script exercises
+    pub tests: TestResults,
+    // The points earned: all of the exercise's points when it passed, and
+    // the share of them matching the share of passing tests when only some
+    // tests of the exercise failed. Only tests the learner can't add count,
+    // see `graded_ratio`.
+    pub score: f64,
+    // The points the exercise is worth
+    pub max_score: u32,
+    // How many of its hints the learner has seen, only reported by
+    // `rustlings cicvverify --hints`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hints_used: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct ExerciseStatistics {
+    pub total_exercations: usize,
+    pub total_succeeds: usize,
+    pub total_failures: usize,
+    pub total_time: u32,
+    // The points earned and the points available over all exercises
+    pub total_score: f64,
+    pub max_score: u32,
+    // The same per topic of the exercises
+    pub topics: BTreeMap<String, TopicScore>,
+}
+
+#[derive(Serialize, Default, PartialEq, Debug)]
+pub struct TopicScore {
+    pub score: f64,
+    pub max_score: u32,
+}
+
+impl ExerciseCheckList {
+    // The results of grading the exercises, with the statistics about them
+    pub fn new(exercises: Vec<ExerciseResult>, total_time: u32) -> Self {
+        let total_succeeds = exercises.iter().filter(|r| r.result).count();
+        ExerciseCheckList {
+            schema_version: SCHEMA_VERSION,
+            user_name: None,
+            statistics: ExerciseStatistics {
+                total_exercations: exercises.len(),
+                total_succeeds,
+                total_failures: exercises.len() - total_succeeds,
+                total_time,
+                total_score: exercises.iter().map(|r| r.score).sum(),
+                max_score: exercises.iter().map(|r| r.max_score).sum(),
+                topics: topic_scores(&exercises),
+            },
+            exercises,
+        }
+    }
+}
+
+// Add up the scores of the exercises of each topic
+pub fn topic_scores(results: &[ExerciseResult]) -> BTreeMap<String, TopicScore> {
+    let mut topics = BTreeMap::<String, TopicScore>::new();
+    for result in results {
+        let topic = topics.entry(result.topic.clone()).or_default();
+        topic.score += result.score;
+        topic.max_score += result.max_score;
+    }
+    topics
+}
+
+// The reason an exercise did not pass
+#[derive(Serialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    // The exercise did not compile
+    Compile,
+    // The exercise compiled, but clippy reported lints
+    Clippy,
+    // At least one test of the exercise failed
+    Test,
+    // The compiled binary exited with an error
+    Runtime,
+    // The exercise was killed for running longer than its timeout
+    Timeout,
+}
+
+// Grade the given exercise inside its own directory below `scratch_root`,
+// so that it can run alongside other exercises being graded.
+pub fn grade_isolated(exercise: &Exercise, scratch_root: &Path) -> ExerciseResult {
+    let dir = scratch_root.join(exercise.file_name());
+    let result = match fs::create_dir_all(&dir) {
+        Ok(()) => grade(exercise, &BuildDir::Private(dir.clone())),
+        Err(e) => {
+            let output = ExerciseOutput {
+                stdout: String::new(),
+                stderr: format!("Failed to create {}: {e}", dir.display()),
+                timed_out: false,
+            };
+            failed(exercise, Failure::Compile, output, 0)
+        }
+    };
+    let _ignored = fs::remove_dir_all(&dir);
+    result
+}
+
+// Compile and run the given exercise without printing anything,
+// recording everything needed to report on it. In the shared build
+// directory the outcome may come from the cache of the exercise.
+pub fn grade(exercise: &Exercise, build_dir: &BuildDir) -> ExerciseResult {
+    let start = Instant::now();
+    let compiled = match build_dir {
+        BuildDir::Shared => exercise.compile(),
+        BuildDir::Private(_) => exercise.compile_in(build_dir),
+    };
+    let (failure, output) = match compiled {
+        Ok(compiled) => match compiled.check() {
+            Ok(output) => (None, output),
+            Err(output) if output.timed_out => (Some(Failure::Timeout), output),
+            Err(output) => (Some(run_failure(exercise.mode)), output),
+        },
+        Err(output) => (Some(compile_failure(exercise.mode, &output)), output),
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    match failure {
+        Some(failure) => failed(exercise, failure, output, duration_ms),
+        None => ExerciseResult {
+            name: exercise.name.clone(),
+            topic: exercise.topic(),
+            result: true,
+            mode: exercise.mode,
+            failure: None,
+            duration_ms,
+            tests: output.test_results(),
+            score: exercise.points() as f64,
+            max_score: exercise.points(),
+            hints_used: None,
+            stdout: strip_ansi_codes(&output.stdout).into_owned(),
+            stderr: strip_ansi_codes(&output.stderr).into_owned(),
+        },
+    }
+}
+
+fn failed(
+    exercise: &Exercise,
+    failure: Failure,
+    output: ExerciseOutput,
+    duration_ms: u64,
+) -> ExerciseResult {
+    let tests = output.test_results();
+    let score = match failure {
+        Failure::Test => partial_score(exercise, &tests, reset::pristine(&exercise.path)),
+        _ => 0.0,
+    };
+    ExerciseResult {
+        name: exercise.name.clone(),
+        topic: exercise.topic(),
+        result: false,
+        mode: exercise.mode,
+        failure: Some(failure),
+        duration_ms,
+        tests,
+        score,
+        max_score: exercise.points(),
+        hints_used: None,
+        stdout: strip_ansi_codes(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&output.stderr).into_owned(),
+    }
+}
+
+// The points earned by an exercise of which only some tests passed
+fn partial_score(exercise: &Exercise, tests: &TestResults, original: Option<&str>) -> f64 {
+    graded_ratio(exercise, tests, original).unwrap_or(0.0) * exercise.points() as f64
+}
+
+// The share of the tests that count towards the score that passed. Those are
+// the hidden tests when the exercise has them, or else the tests of the
+// original exercise, so that tests added by the learner don't count and
+// original tests the learner removed count as failed. Without either there
+// is no partial credit.
+fn graded_ratio(exercise: &Exercise, tests: &TestResults, original: Option<&str>) -> Option<f64> {
+    if exercise.hidden_tests.is_some() {
+        return tests.within(HIDDEN_TESTS_MODULE).ratio();
+    }
+    let graded = test_names(original?);
+    if graded.is_empty() {
+        return None;
+    }
+    // Tests are reported with the modules they're in, like `tests::works`
+    let ran = |results: &[String], name: &str| {
+        results.iter().any(|test| test.rsplit("::").next() == Some(name))
+    };
+    let passed = graded
+        .iter()
+        .filter(|name| ran(&tests.passed, name) && !ran(&tests.failed, name))
+        .count();
+    Some(passed as f64 / graded.len() as f64)
+}
+
+// The names of the test functions in the given source
+fn test_names(source: &str) -> BTreeSet<String> {
+    let test = Regex::new(r"#\[test\](?:\s*#\[[^\]]*\])*\s*(?:pub\s+)?fn\s+(\w+)").unwrap();
+    test.captures_iter(source)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+fn compile_failure(mode: Mode, output: &ExerciseOutput) -> Failure {
+    if output.timed_out {
+        return Failure::Timeout;
+    }
+    match mode {
+        // Lints denied by `-D warnings` are reported as `clippy::<lint>`
+        Mode::Clippy if output.stderr.contains("clippy::") => Failure::Clippy,
+        // Build script exercises are compiled and tested in one `cargo test`
+        Mode::BuildScript if output.stdout.contains("test result: FAILED") => Failure::Test,
+        _ => Failure::Compile,
+    }
+}
+
+fn run_failure(mode: Mode) -> Failure {
+    match mode {
+        Mode::Test | Mode::BuildScript | Mode::Cargo => Failure::Test,
+        Mode::Compile | Mode::Clippy => Failure::Runtime,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn exercise(path: &str, mode: Mode) -> Exercise {
+        Exercise::new("example", path, mode)
+    }
+
+    #[test]
+    fn test_grade_success() {
+        let result = grade(
+            &exercise("tests/fixture/success/testSuccess.rs", Mode::Test),
+            &BuildDir::Shared,
+        );
+        assert!(result.result);
+        assert_eq!(result.failure, None);
+        assert_eq!(result.tests.passed, vec!["passing".to_string()]);
+        assert!(result.stdout.contains("THIS TEST TOO SHALL PASS"));
+        assert_eq!(result.score, 1.0);
+    }
+
+    #[test]
+    fn test_grade_compile_failure() {
+        let result = grade(
+            &exercise("tests/fixture/failure/compFailure.rs", Mode::Compile),
+            &BuildDir::Shared,
+        );
+        assert!(!result.result);
+        assert_eq!(result.failure, Some(Failure::Compile));
+        assert!(result.stderr.contains("error"));
+        assert!(!result.stderr.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_grade_test_failure() {
+        let result = grade(
+            &exercise("tests/fixture/failure/testNotPassed.rs", Mode::Test),
+            &BuildDir::Shared,
+        );
+        assert_eq!(result.failure, Some(Failure::Test));
+        assert_eq!(result.tests.failed, vec!["not_passing".to_string()]);
+        assert_eq!(result.score, 0.0);
+    }
+
+    #[test]
+    fn test_grade_partial_credit() {
+        let result = grade(
+            &exercise("tests/fixture/failure/testPartial.rs", Mode::Test),
+            &BuildDir::Shared,
+        );
+        assert_eq!(result.failure, Some(Failure::Test));
+        assert_eq!(result.tests.to_string(), "3/4 tests passing");
+        // Not one of the embedded exercises, so there are no original tests to go by
+        assert_eq!(result.score, 0.0);
+
+        let original = fs::read_to_string("tests/fixture/failure/testPartial.rs").unwrap();
+        let mut weighted = exercise("tests/fixture/failure/testPartial.rs", Mode::Test);
+        assert_eq!(partial_score(&weighted, &result.tests, Some(&original)), 0.75);
+        weighted.metadata.points = Some(4);
+        assert_eq!(partial_score(&weighted, &result.tests, Some(&original)), 3.0);
+    }
+
+    #[test]
+    fn test_graded_ratio_ignores_added_tests() {
+        let original = "#[test]\nfn first() {}\n\n#[test]\n#[should_panic]\nfn second() {}\n";
+        let tests = |passed: &[&str], failed: &[&str]| TestResults {
+            passed: passed.iter().map(|t| t.to_string()).collect(),
+            failed: failed.iter().map(|t| t.to_string()).collect(),
+        };
+        let exercise = exercise("exercises/example.rs", Mode::Test);
+        let ratio = |tests: &TestResults| graded_ratio(&exercise, tests, Some(original));
+        assert_eq!(ratio(&tests(&["tests::first"], &["tests::second"])), Some(0.5));
+        // Added tests don't count, removed ones count as failed
+        assert_eq!(ratio(&tests(&["tests::first", "mine1", "mine2"], &["tests::second"])), Some(0.5));
+        assert_eq!(ratio(&tests(&["mine1", "mine2", "mine3"], &[])), Some(0.0));
+        assert_eq!(graded_ratio(&exercise, &tests(&["mine1"], &[]), None), None);
+    }
+
+    #[test]
+    fn test_grade_counts_only_hidden_tests() {
+        // The learner's own tests pass, the hidden one doesn't
+        let dir = std::env::temp_dir().join(format!("rustlings_test_hidden_score_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut source = fs::read_to_string("tests/fixture/hidden_tests/sum.rs").unwrap();
+        source.push_str("\n#[test]\nfn mine1() {}\n\n#[test]\nfn mine2() {}\n\n#[test]\nfn mine3() {}\n");
+        fs::write(dir.join("sum.rs"), source).unwrap();
+        let mut sum = exercise(&dir.join("sum.rs").to_string_lossy(), Mode::Test);
+        sum.hidden_tests = Some(PathBuf::from("tests/fixture/hidden_tests/hidden_tests/sum.rs"));
+
+        let result = grade_isolated(&sum, &dir.join("scratch"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.failure, Some(Failure::Test));
+        assert_eq!(result.tests.to_string(), "3/4 tests passing");
+        assert_eq!(result.score, 0.0);
+    }
+
+    #[test]
+    fn test_grade_clippy_without_running() {
+        // Like `rustlings verify`, clippy exercises pass once they are lint free
+        let dir = std::env::temp_dir().join(format!("rustlings_test_clippy_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("clippy.rs"), "fn main() {\n    panic!(\"not run\");\n}\n").unwrap();
+        let clippy = exercise(&dir.join("clippy.rs").to_string_lossy(), Mode::Clippy);
+        let result = grade_isolated(&clippy, &dir.join("scratch"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.failure, None);
+    }
+
+    #[test]
+    fn test_topic_scores() {
+        let result = |topic: &str, score: f64, max_score: u32| ExerciseResult {
+            name: "example".into(),
+            topic: topic.into(),
+            result: score == max_score as f64,
+            mode: Mode::Test,
+            failure: None,
+            duration_ms: 0,
+            stdout: String::new(),
+            stderr: String::new(),
+            tests: TestResults::default(),
+            score,
+            max_score,
+            hints_used: None,
+        };
+        let topics = topic_scores(&[
+            result("enums", 2.0, 2),
+            result("traits", 0.5, 1),
+            result("enums", 0.0, 3),
+        ]);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics["enums"], TopicScore { score: 2.0, max_score: 5 });
+        assert_eq!(topics["traits"], TopicScore { score: 0.5, max_score: 1 });
+    }
+
+    #[test]
+    fn test_grade_isolated_cleans_up() {
+        let scratch_root = std::env::temp_dir().join("rustlings_test_grade_isolated");
+        let result = grade_isolated(
+            &exercise("tests/fixture/success/compSuccess.rs", Mode::Compile),
+            &scratch_root,
+        );
+        assert!(result.result);
+        assert!(!scratch_root.join("example").exists());
+    }
+
+    #[test]
+    fn test_grade_timeout() {
+        let mut exercise = exercise("tests/fixture/failure/infiniteLoop.rs", Mode::Compile);
+        exercise.timeout = Some(1);
+        let result = grade(&exercise, &BuildDir::Shared);
+        assert_eq!(result.failure, Some(Failure::Timeout));
+    }
+}
@@ -1,18 +1,35 @@
+use crate::cache::{BuildCache, CachedOutcome};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Command, Output, Stdio};
+use std::str::FromStr;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+// The edition the exercises are written in
+pub const EDITION: &str = "2021";
+const RUSTC_EDITION_ARGS: &[&str] = &["--edition", EDITION];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+const TEST_LINE_REGEX: &str = r"^test (\S+) \.\.\. (ok|FAILED)";
 const CONTEXT: usize = 2;
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
 const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+// The cargo workspace the projects of cargo exercises are generated into
+pub const CARGO_WORKSPACE_DIR: &str = ".rustlings-cargo";
+// The dependencies of cargo exercises, as written by `rustlings vendor`
+pub const VENDOR_DIR: &str = "vendor";
+// The module the hidden tests of an exercise are compiled into
+pub const HIDDEN_TESTS_MODULE: &str = "rustlings_hidden_tests";
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -26,7 +43,7 @@ fn temp_file() -> String {
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -37,16 +54,156 @@ pub enum Mode {
     Clippy,
     // Indicates that the exercise should be run using cargo with build script
     BuildScript,
+    // Indicates that the exercise should be tested as a cargo project,
+    // which can use the crates listed in its `dependencies`
+    Cargo,
 }
 
-#[derive(Deserialize)]
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "compile" => Ok(Mode::Compile),
+            "test" => Ok(Mode::Test),
+            "clippy" => Ok(Mode::Clippy),
+            "buildscript" => Ok(Mode::BuildScript),
+            "cargo" => Ok(Mode::Cargo),
+            _ => Err(format!(
+                "unknown mode `{s}`, expected compile, test, clippy, buildscript or cargo"
+            )),
+        }
+    }
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Mode::Compile => "compile",
+            Mode::Test => "test",
+            Mode::Clippy => "clippy",
+            Mode::BuildScript => "buildscript",
+            Mode::Cargo => "cargo",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// The name of the track of the exercises in `info.toml`, unless it sets `track`
+const MAIN_TRACK: &str = "main";
+
+#[derive(Deserialize, Debug)]
 pub struct ExerciseList {
+    // The name of the track formed by the exercises of this manifest.
+    // Defaults to "main" for `info.toml` and to the file name for included manifests.
+    #[serde(default)]
+    pub track: Option<String>,
+    // Further manifests to load, relative to this one.
+    // Only `info.toml` itself may include other manifests.
+    #[serde(default)]
+    pub include: Vec<PathBuf>,
     pub exercises: Vec<Exercise>,
 }
 
+impl ExerciseList {
+    // Load the manifest at `path` along with the manifests it includes,
+    // keeping only the exercises of the given tracks (or all of them if none
+    // are given). The exercises of included manifests are named `<track>/<name>`,
+    // so that several tracks can use the same names. Exercise paths are
+    // relative to the rustlings directory in every manifest.
+    pub fn load(path: &Path, tracks: &[String]) -> Result<ExerciseList, String> {
+        let mut list = Self::parse(path)?;
+        let main_track = list.track.take().unwrap_or_else(|| MAIN_TRACK.to_string());
+        let mut loaded = vec![(main_track, std::mem::take(&mut list.exercises))];
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for include in std::mem::take(&mut list.include) {
+            let include = dir.join(include);
+            let mut included = Self::parse(&include)?;
+            if !included.include.is_empty() {
+                return Err(format!(
+                    "{}: only {} can include other manifests",
+                    include.display(),
+                    path.display()
+                ));
+            }
+            let track = match included.track {
+                Some(track) => track,
+                None => include
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .ok_or_else(|| format!("{}: not a manifest file", include.display()))?,
+            };
+            if loaded.iter().any(|(name, _)| *name == track) {
+                return Err(format!("{}: there already is a track named `{track}`", include.display()));
+            }
+            for exercise in &mut included.exercises {
+                exercise.name = format!("{track}/{}", exercise.name);
+                for prerequisite in &mut exercise.metadata.prerequisites {
+                    *prerequisite = format!("{track}/{prerequisite}");
+                }
+            }
+            loaded.push((track, included.exercises));
+        }
+
+        if let Some(unknown) = tracks.iter().find(|t| !loaded.iter().any(|(name, _)| name == *t)) {
+            let known = loaded.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+            return Err(format!(
+                "There is no track named `{unknown}`, the tracks are: {}",
+                known.join(", ")
+            ));
+        }
+        list.exercises = loaded
+            .into_iter()
+            .filter(|(name, _)| tracks.is_empty() || tracks.contains(name))
+            .flat_map(|(_, exercises)| exercises)
+            .collect();
+        Ok(list)
+    }
+
+    fn parse(path: &Path) -> Result<ExerciseList, String> {
+        let toml_str = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        toml::from_str(&toml_str).map_err(|e| format!("{} is invalid: {e}", path.display()))
+    }
+
+    // Check the optional metadata of the exercises, returning every problem found
+    pub fn validate_metadata(&self) -> Vec<String> {
+        let mut problems = vec![];
+        for (i, exercise) in self.exercises.iter().enumerate() {
+            let metadata = &exercise.metadata;
+            for prerequisite in &metadata.prerequisites {
+                match self.exercises.iter().position(|e| &e.name == prerequisite) {
+                    None => problems.push(format!(
+                        "{}: unknown prerequisite `{prerequisite}`",
+                        exercise.name
+                    )),
+                    Some(j) if j >= i => problems.push(format!(
+                        "{}: prerequisite `{prerequisite}` comes after it in info.toml",
+                        exercise.name
+                    )),
+                    Some(_) => {}
+                }
+            }
+            if metadata.estimated_minutes == Some(0) {
+                problems.push(format!("{}: `estimated_minutes` must be positive", exercise.name));
+            }
+            for url in &metadata.book {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    problems.push(format!("{}: `{url}` is not a URL", exercise.name));
+                }
+            }
+            if metadata.tags.iter().any(|tag| tag.trim().is_empty()) {
+                problems.push(format!("{}: tags must not be empty", exercise.name));
+            }
+        }
+        problems
+    }
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -54,8 +211,116 @@ pub struct Exercise {
     pub path: PathBuf,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
-    // The hint text associated with the exercise
-    pub hint: String,
+    // The hints of the exercise, each one giving away more of the solution
+    // than the one before. A single hint is written as a plain string.
+    #[serde(rename = "hint", deserialize_with = "one_or_more")]
+    pub hints: Vec<String>,
+    // How many seconds the exercise may run, overriding the global default
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // The crates a cargo exercise depends on, in the format of the
+    // `[dependencies]` section of a Cargo.toml
+    #[serde(default)]
+    pub dependencies: toml::value::Table,
+    // Other files the outcome of the exercise depends on, such as a build
+    // script shared with other exercises. Relative to the rustlings directory.
+    #[serde(default)]
+    pub watch: Vec<PathBuf>,
+    // Tests the learner doesn't get to edit, compiled into test and cargo
+    // exercises next to their own tests. Relative to the rustlings directory.
+    #[serde(default)]
+    pub hidden_tests: Option<PathBuf>,
+    // A solved version of the exercise, which `rustlings check-info` checks
+    #[serde(default)]
+    pub solution: Option<PathBuf>,
+    // The resource limits applied when running the exercise
+    #[serde(skip)]
+    pub limits: Limits,
+    // Where to look up and store the outcome of the exercise, if anywhere
+    #[serde(skip)]
+    pub cache: Option<BuildCache>,
+    // Optional information for building curricula out of the exercises
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+// How hard an exercise is
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMore {
+    One(String),
+    More(Vec<String>),
+}
+
+fn one_or_more<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match OneOrMore::deserialize(deserializer)? {
+        OneOrMore::One(hint) => vec![hint],
+        OneOrMore::More(hints) => hints,
+    })
+}
+
+// The optional metadata of an exercise in info.toml
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct Metadata {
+    // The topic of the exercise, defaults to the name of its directory
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    // Roughly how long solving the exercise takes
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    // Names of exercises that should be solved first
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    // URLs of the related chapters of the Rust Book
+    #[serde(default)]
+    pub book: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // How much the exercise counts towards the grade, 1 if not set
+    #[serde(default)]
+    pub points: Option<u32>,
+}
+
+// The resources an exercise may use while it runs
+#[derive(Copy, Clone, Debug)]
+pub struct Limits {
+    // How long the exercise may run before it gets killed
+    pub timeout: Duration,
+    // The maximum size of the address space in MiB (Linux only)
+    pub memory_mb: Option<u64>,
+    // The maximum CPU time in seconds (Linux only)
+    pub cpu_secs: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            timeout: DEFAULT_TIMEOUT,
+            memory_mb: None,
+            cpu_secs: None,
+        }
+    }
 }
 
 // An enum to track of the state of an Exercise.
@@ -82,69 +347,242 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
-    _handle: FileHandle,
+    // The outcome of running the exercise if it is already known: build script
+    // exercises are tested while compiling, and others may come from the cache
+    outcome: Option<Result<ExerciseOutput, ExerciseOutput>>,
+    // The cache key to store the outcome of running the exercise under
+    cache_key: Option<String>,
+    handle: Option<FileHandle>,
 }
 
 impl<'a> CompiledExercise<'a> {
     // Run the compiled exercise
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        let binary = match (&self.outcome, &self.handle) {
+            (Some(outcome), _) => return outcome.clone(),
+            (None, Some(handle)) => &handle.0,
+            (None, None) => unreachable!("a compiled exercise has either an outcome or a binary"),
+        };
+        let result = self.exercise.run(binary);
+        if let (Some(cache), Some(key)) = (&self.exercise.cache, &self.cache_key) {
+            let outcome = CachedOutcome::ran(key.clone(), &result);
+            cache.store(self.exercise, &outcome);
+        }
+        result
+    }
+
+    // Check the exercise the way `rustlings verify` does: clippy exercises pass
+    // once they compile without lints, all others have to run successfully.
+    // A clippy exercise that passes this way isn't cached, or `run` would
+    // replay the pass without ever running it.
+    pub fn check(&self) -> Result<ExerciseOutput, ExerciseOutput> {
+        if self.exercise.mode != Mode::Clippy {
+            return self.run();
+        }
+        Ok(ExerciseOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            timed_out: false,
+        })
     }
 }
 
 // A representation of an already executed binary
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ExerciseOutput {
     // The textual contents of the standard output of the binary
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // Whether the binary was killed for running longer than allowed
+    pub timed_out: bool,
 }
 
-struct FileHandle;
+// The names of the individual tests reported by a test harness
+#[derive(Serialize, Default, PartialEq, Debug)]
+pub struct TestResults {
+    pub passed: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl TestResults {
+    // The share of the tests that passed, `None` if no tests ran
+    pub fn ratio(&self) -> Option<f64> {
+        let total = self.passed.len() + self.failed.len();
+        (total > 0).then(|| self.passed.len() as f64 / total as f64)
+    }
+
+    // The tests inside the given module
+    pub fn within(&self, module: &str) -> TestResults {
+        let prefix = format!("{module}::");
+        let within = |tests: &[String]| {
+            tests.iter().filter(|t| t.starts_with(&prefix)).cloned().collect()
+        };
+        TestResults {
+            passed: within(&self.passed),
+            failed: within(&self.failed),
+        }
+    }
+}
+
+impl Display for TestResults {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let total = self.passed.len() + self.failed.len();
+        write!(f, "{}/{total} tests passing", self.passed.len())
+    }
+}
+
+impl ExerciseOutput {
+    fn new(output: &Output, timed_out: bool) -> Self {
+        ExerciseOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            timed_out,
+        }
+    }
+
+    // Collect the `test <name> ... ok|FAILED` lines printed by libtest
+    pub fn test_results(&self) -> TestResults {
+        let re = Regex::new(TEST_LINE_REGEX).unwrap();
+        let mut results = TestResults::default();
+        for captures in self.stdout.lines().filter_map(|line| re.captures(line)) {
+            let name = captures[1].to_string();
+            if &captures[2] == "ok" {
+                results.passed.push(name);
+            } else {
+                results.failed.push(name);
+            }
+        }
+        results
+    }
+}
+
+// Where the artifacts of compiling an exercise are written to
+pub enum BuildDir {
+    // A temporary file next to `info.toml` plus the Cargo.toml files inside
+    // `exercises/`. Only one exercise can be built at a time this way.
+    Shared,
+    // A scratch directory owned by a single exercise, so that several
+    // exercises can be built at the same time without clobbering each other
+    Private(PathBuf),
+}
+
+impl BuildDir {
+    fn binary(&self, exercise: &Exercise) -> PathBuf {
+        match self {
+            BuildDir::Shared => PathBuf::from(temp_file()),
+            BuildDir::Private(dir) => dir.join(exercise.file_name()),
+        }
+    }
+}
+
+struct FileHandle(PathBuf);
 
 impl Drop for FileHandle {
     fn drop(&mut self) {
-        clean();
+        let _ignored = remove_file(&self.0);
     }
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    // Whether changing the file at `path` can change the outcome of the exercise
+    pub fn depends_on(&self, path: &Path) -> bool {
+        path.ends_with(&self.path)
+            || self.watch.iter().any(|file| path.ends_with(file))
+            || self.hidden_tests.as_ref().is_some_and(|file| path.ends_with(file))
+    }
+
+    // Compile the exercise in the shared build directory, reusing its cached
+    // outcome instead if nothing changed since it was last built
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        let cache_key = self.cache.as_ref().and_then(|cache| cache.key(self));
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(cached) = cache.lookup(self, key) {
+                if !cached.compiled {
+                    return Err(cached.output);
+                }
+                return Ok(CompiledExercise {
+                    exercise: self,
+                    outcome: Some(cached.into_run_result()),
+                    cache_key: None,
+                    handle: None,
+                });
+            }
+        }
+
+        let result = self.compile_in(&BuildDir::Shared);
+        let (Some(cache), Some(key)) = (&self.cache, cache_key) else {
+            return result;
+        };
+        match result {
+            Ok(mut compiled) => {
+                match &compiled.outcome {
+                    Some(outcome) => cache.store(self, &CachedOutcome::ran(key, outcome)),
+                    // Stored once the exercise has been run
+                    None => compiled.cache_key = Some(key),
+                }
+                Ok(compiled)
+            }
+            Err(output) => {
+                cache.store(self, &CachedOutcome::failed_to_compile(key, &output));
+                Err(output)
+            }
+        }
+    }
+
+    pub fn compile_in(
+        &self,
+        build_dir: &BuildDir,
+    ) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        let binary = build_dir.binary(self);
+        if let Some(hidden_tests) = &self.hidden_tests {
+            if let Err(e) = fs::metadata(hidden_tests) {
+                return Err(ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: format!("Can't read the hidden tests {}: {e}", hidden_tests.display()),
+                    timed_out: false,
+                });
+            }
+        }
+        let mut compile_timed_out = false;
         let cmd = match self.mode {
             Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
+                .arg(&self.path)
+                .arg("-o")
+                .arg(&binary)
                 .args(RUSTC_COLOR_ARGS)
                 .args(RUSTC_EDITION_ARGS)
                 .output(),
+            Mode::Test => {
+                let wrapper = FileHandle(binary.with_extension("rs"));
+                self.with_hidden_tests(&wrapper.0).and_then(|source| {
+                    Command::new("rustc")
+                        .arg("--test")
+                        .arg(source)
+                        .arg("-o")
+                        .arg(&binary)
+                        .args(RUSTC_COLOR_ARGS)
+                        .args(RUSTC_EDITION_ARGS)
+                        .output()
+                })
+            }
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
                 let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
                 };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                let manifest = self
+                    .write_cargo_toml(build_dir, CLIPPY_CARGO_TOML_PATH)
+                    .expect(cargo_toml_error_msg);
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
                 Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .arg(&self.path)
+                    .arg("-o")
+                    .arg(&binary)
                     .args(RUSTC_COLOR_ARGS)
                     .args(RUSTC_EDITION_ARGS)
                     .output()
@@ -153,75 +591,207 @@ path = "{}.rs""#,
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
                 // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
                 // https://github.com/rust-lang/rust-clippy/issues/3837
+                // A private build directory starts out clean anyway.
+                if let BuildDir::Shared = build_dir {
+                    Command::new("cargo")
+                        .arg("clean")
+                        .arg("--manifest-path")
+                        .arg(&manifest)
+                        .args(RUSTC_COLOR_ARGS)
+                        .output()
+                        .expect("Failed to run 'cargo clean'");
+                }
                 Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .arg("clippy")
+                    .arg("--manifest-path")
+                    .arg(&manifest)
                     .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
-            },
+            }
+            Mode::Cargo => self.build_cargo_project(build_dir, &binary),
             Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                let manifest = self
+                    .write_cargo_toml(build_dir, BUILD_SCRIPT_CARGO_TOML_PATH)
+                    .expect("Failed to write build script Cargo.toml file.");
 
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
-                    .output()
+                // The tests are run as part of `cargo test`, so this is
+                // where the timeout of build script exercises applies.
+                let mut command = Command::new("cargo");
+                command.arg("test").arg("--manifest-path").arg(&manifest);
+                output_with_timeout(&mut command, self.limits().timeout, None)
+                    .map(|(output, timed_out)| {
+                        compile_timed_out = timed_out;
+                        output
+                    })
             }
         }
         .expect("Failed to run 'compile' command.");
 
         if cmd.status.success() {
+            let outcome = match self.mode {
+                Mode::BuildScript => Some(Ok(ExerciseOutput::new(&cmd, false))),
+                _ => None,
+            };
             Ok(CompiledExercise {
                 exercise: self,
-                _handle: FileHandle,
+                outcome,
+                cache_key: None,
+                handle: Some(FileHandle(binary)),
             })
         } else {
-            clean();
-            Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-            })
+            let _ignored = remove_file(&binary);
+            Err(ExerciseOutput::new(&cmd, compile_timed_out))
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
+    // The source to compile: the exercise itself, or a file written to `wrapper`
+    // that includes the exercise and adds its hidden tests as a test module.
+    // Including keeps the compiler pointing at the exercise in its messages.
+    fn with_hidden_tests(&self, wrapper: &Path) -> io::Result<PathBuf> {
+        let Some(hidden_tests) = &self.hidden_tests else {
+            return Ok(self.path.clone());
+        };
+        let content = format!(
+            "include!({:?});\n\n#[cfg(test)]\n#[path = {:?}]\nmod {HIDDEN_TESTS_MODULE};\n",
+            fs::canonicalize(&self.path)?,
+            fs::canonicalize(hidden_tests)?
+        );
+        fs::write(wrapper, content)?;
+        Ok(wrapper.to_path_buf())
+    }
+
+    // Write the Cargo.toml of the clippy and build script exercises and
+    // return its path. The shared manifest lives next to the exercise,
+    // a private one points back to the exercise and its build script.
+    fn write_cargo_toml(&self, build_dir: &BuildDir, shared_path: &str) -> io::Result<PathBuf> {
+        let (manifest, source, build_script) = match build_dir {
+            BuildDir::Shared => {
+                let source = self.path.file_name().unwrap_or_default().to_string_lossy();
+                (PathBuf::from(shared_path), source.to_string(), None)
+            }
+            BuildDir::Private(dir) => {
+                let source = fs::canonicalize(&self.path)?;
+                let build_script = match self.mode {
+                    Mode::BuildScript => source.parent().map(|dir| dir.join("build.rs")),
+                    _ => None,
+                };
+                (dir.join("Cargo.toml"), source.display().to_string(), build_script)
+            }
+        };
+        let build = build_script
+            .map(|path| format!("build = '{}'\n", path.display()))
+            .unwrap_or_default();
+        let cargo_toml = format!(
+            r#"[package]
+name = "{}"
+version = "0.0.1"
+edition = "{EDITION}"
+{build}[[bin]]
+name = "{}"
+path = '{source}'"#,
+            self.file_name(),
+            self.file_name()
+        );
+        fs::write(&manifest, cargo_toml)?;
+        Ok(manifest)
+    }
+
+    // Build the tests of a cargo exercise and copy the test binary to `binary`.
+    // Cargo reports on its progress as JSON, only the rendered diagnostics
+    // are kept in the output.
+    fn build_cargo_project(&self, build_dir: &BuildDir, binary: &Path) -> io::Result<Output> {
+        let manifest = self.write_cargo_project(build_dir)?;
+        let mut command = Command::new("cargo");
+        command
+            .args(["test", "--no-run", "--message-format", "json-render-diagnostics"])
+            .arg("--manifest-path")
+            .arg(&manifest)
+            // Shared by all cargo exercises, so that the dependencies are built only once
+            .arg("--target-dir")
+            .arg(Path::new(CARGO_WORKSPACE_DIR).join("target"))
+            .args(RUSTC_COLOR_ARGS);
+        if let Ok(vendor_dir) = fs::canonicalize(VENDOR_DIR) {
+            command
+                .arg("--offline")
+                .arg("--config")
+                .arg("source.crates-io.replace-with='vendored-sources'")
+                .arg("--config")
+                .arg(format!("source.vendored-sources.directory='{}'", vendor_dir.display()));
+        }
+        let mut output = command.output()?;
+
+        let executable = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|message| message["reason"] == "compiler-artifact")
+            .filter(|message| message["profile"]["test"] == true)
+            .find_map(|message| message["executable"].as_str().map(PathBuf::from));
+        output.stdout.clear();
+        if output.status.success() {
+            let executable = executable.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "cargo built no test binary")
+            })?;
+            fs::copy(executable, binary)?;
+        }
+        Ok(output)
+    }
+
+    // Generate the cargo project of a cargo exercise and return the path of
+    // its manifest. In the shared build directory the project is a member of
+    // the cargo workspace of all cargo exercises. A project in a private
+    // build directory is a workspace of its own, so that it can be generated
+    // while other exercises are being built.
+    pub fn write_cargo_project(&self, build_dir: &BuildDir) -> io::Result<PathBuf> {
+        let dir = match build_dir {
+            BuildDir::Shared => {
+                let workspace = Path::new(CARGO_WORKSPACE_DIR);
+                let dir = workspace.join("exercises").join(self.file_name());
+                fs::create_dir_all(&dir)?;
+                fs::write(
+                    workspace.join("Cargo.toml"),
+                    "[workspace]\nresolver = \"2\"\nmembers = [\"exercises/*\"]\n",
+                )?;
+                dir
+            }
+            BuildDir::Private(dir) => dir.clone(),
+        };
+
+        let mut package = toml::value::Table::new();
+        package.insert("name".into(), self.file_name().into());
+        package.insert("version".into(), "0.0.1".into());
+        package.insert("edition".into(), EDITION.into());
+        package.insert("publish".into(), false.into());
+        let mut lib = toml::value::Table::new();
+        let source = fs::canonicalize(self.with_hidden_tests(&dir.join("hidden_tests.rs"))?)?;
+        lib.insert("path".into(), source.display().to_string().into());
+        lib.insert("doctest".into(), false.into());
+        let mut cargo_toml = toml::value::Table::new();
+        cargo_toml.insert("package".into(), package.into());
+        cargo_toml.insert("lib".into(), lib.into());
+        cargo_toml.insert("dependencies".into(), self.dependencies.clone().into());
+        if let BuildDir::Private(_) = build_dir {
+            cargo_toml.insert("workspace".into(), toml::value::Table::new().into());
+        }
+
+        let manifest = dir.join("Cargo.toml");
+        let content = toml::to_string(&toml::Value::Table(cargo_toml))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&manifest, content)?;
+        Ok(manifest)
+    }
+
+    fn run(&self, binary: &Path) -> Result<ExerciseOutput, ExerciseOutput> {
         let arg = match self.mode {
-            Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
-            }),
+            Mode::Test | Mode::Cargo => "--show-output",
             _ => "",
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        let limits = self.limits();
+        let (cmd, timed_out) =
+            output_with_timeout(Command::new(binary).arg(arg), limits.timeout, Some(limits))
+                .expect("Failed to run 'run' command");
 
-        let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-        };
+        let output = ExerciseOutput::new(&cmd, timed_out);
 
         if cmd.status.success() {
             Ok(output)
@@ -230,6 +800,133 @@ path = "{}.rs""#,
         }
     }
 
+    // The name of the exercise in a form usable in file and package names,
+    // as the names of exercises from included tracks contain a `/`
+    pub fn file_name(&self) -> String {
+        self.name.replace('/', "_")
+    }
+
+    // The limits to run the exercise with, honoring its own timeout
+    pub fn limits(&self) -> Limits {
+        Limits {
+            timeout: self.timeout.map_or(self.limits.timeout, Duration::from_secs),
+            ..self.limits
+        }
+    }
+
+    // The topic of the exercise, either set in info.toml or the name of its directory
+    pub fn topic(&self) -> String {
+        if let Some(topic) = &self.metadata.topic {
+            return topic.clone();
+        }
+        self.path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map_or_else(String::new, |dir| dir.to_string_lossy().to_string())
+    }
+
+    // An exercise with nothing but a name, a path and a mode, for tests to
+    // adjust to their needs
+    #[cfg(test)]
+    pub fn new(name: &str, path: impl Into<PathBuf>, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: path.into(),
+            mode,
+            hints: vec![],
+            timeout: None,
+            dependencies: toml::value::Table::new(),
+            watch: vec![],
+            hidden_tests: None,
+            solution: None,
+            limits: Limits::default(),
+            cache: None,
+            metadata: Metadata::default(),
+        }
+    }
+
+    // The exercise with its reference solution in place of its source, if it has one
+    pub fn solved(&self) -> Option<Exercise> {
+        Some(Exercise {
+            path: self.solution.clone()?,
+            solution: None,
+            cache: None,
+            ..self.clone()
+        })
+    }
+
+    // How much the exercise counts towards the grade
+    pub fn points(&self) -> u32 {
+        self.metadata.points.unwrap_or(1)
+    }
+
+    // A one line summary of the metadata set in info.toml, if there is any
+    pub fn metadata_summary(&self) -> Option<String> {
+        let metadata = &self.metadata;
+        let mut parts = vec![];
+        if let Some(topic) = &metadata.topic {
+            parts.push(format!("topic: {topic}"));
+        }
+        if let Some(difficulty) = metadata.difficulty {
+            parts.push(format!("difficulty: {difficulty}"));
+        }
+        if let Some(minutes) = metadata.estimated_minutes {
+            parts.push(format!("about {minutes} min"));
+        }
+        if !metadata.tags.is_empty() {
+            parts.push(format!("tags: {}", metadata.tags.join(", ")));
+        }
+        (!parts.is_empty()).then(|| parts.join(" | "))
+    }
+
+    pub fn hint_levels(&self) -> usize {
+        self.hints.len()
+    }
+
+    // All of the hints
+    pub fn full_hint(&self) -> String {
+        self.hint(self.hints.len())
+    }
+
+    // The first `levels` hints, numbered if there is more than one, followed
+    // by the prerequisites and Rust Book chapters if there are any
+    pub fn hint(&self, levels: usize) -> String {
+        let shown = &self.hints[..levels.min(self.hints.len())];
+        let mut hint = if self.hints.len() > 1 {
+            shown
+                .iter()
+                .enumerate()
+                .map(|(i, hint)| format!("Hint {}/{}: {hint}", i + 1, self.hints.len()))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        } else {
+            shown.join("\n\n")
+        };
+        let metadata = &self.metadata;
+        if !metadata.prerequisites.is_empty() {
+            hint.push_str(&format!(
+                "\n\nThis exercise builds on: {}",
+                metadata.prerequisites.join(", ")
+            ));
+        }
+        if !metadata.book.is_empty() {
+            hint.push_str("\n\nRelated chapters of the Rust Book:");
+            for url in &metadata.book {
+                hint.push_str(&format!("\n  {url}"));
+            }
+        }
+        hint
+    }
+
+    // A hex encoded SHA-256 of the exercise's source code
+    pub fn source_hash(&self) -> io::Result<String> {
+        let source = fs::read(&self.path)?;
+        Ok(Sha256::digest(source)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
     pub fn state(&self) -> State {
         let mut source_file =
             File::open(&self.path).expect("We were unable to open the exercise file!");
@@ -242,12 +939,12 @@ path = "{}.rs""#,
             s
         };
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
-
-        if !re.is_match(&source) {
+        if !has_marker(&source) {
             return State::Done;
         }
 
+        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
         let matched_line_index = source
             .lines()
             .enumerate()
@@ -275,8 +972,8 @@ path = "{}.rs""#,
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
-    // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
+    // That's why it's only used in the legacy marker mode, otherwise the
+    // progress state records which exercises actually passed.
     pub fn looks_done(&self) -> bool {
         self.state() == State::Done
     }
@@ -288,9 +985,198 @@ impl Display for Exercise {
     }
 }
 
-#[inline]
-fn clean() {
-    let _ignored = remove_file(&temp_file());
+// The path without its `.` components, so that paths written as `./a.rs` in
+// one place and `a.rs` in another compare equal
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+// Whether the source still contains the `I AM NOT DONE` comment
+pub fn has_marker(source: &str) -> bool {
+    Regex::new(I_AM_DONE_REGEX).unwrap().is_match(source)
+}
+
+// Run the given command like `Command::output`, but kill it once it has been
+// running for longer than `timeout`. The returned flag tells whether it was killed.
+// If `rlimits` is given, its memory and CPU limits are applied to the child process.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+    rlimits: Option<Limits>,
+) -> io::Result<(Output, bool)> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Put the child in its own process group, so that anything it spawned
+        // (like the test binary run by `cargo test`) can be killed along with it.
+        // See `kill_exercises_on_exit` for Ctrl-C, which no longer reaches it.
+        command.process_group(0);
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(limits) = rlimits {
+        set_rlimits(command, limits);
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = rlimits;
+
+    let mut child = command.spawn()?;
+    #[cfg(unix)]
+    let _running = RunningGroup::track(&child);
+    // Drain the pipes while waiting, a child blocked on a full pipe never exits
+    let stdout = child.stdout.take().map(read_to_end_in_background);
+    let stderr = child.stderr.take().map(read_to_end_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            timed_out = true;
+            kill(&mut child);
+            break child.wait()?;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let join = |handle: Option<thread::JoinHandle<Vec<u8>>>| {
+        handle.map(|h| h.join().unwrap_or_default()).unwrap_or_default()
+    };
+    let output = Output {
+        status,
+        stdout: join(stdout),
+        stderr: join(stderr),
+    };
+    Ok((output, timed_out))
+}
+
+fn read_to_end_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ignored = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+#[cfg(unix)]
+fn kill(child: &mut process::Child) {
+    // SAFETY: the child is the leader of its own process group, so this only
+    // signals the child and the processes it spawned.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut process::Child) {
+    let _ignored = child.kill();
+}
+
+// The process groups of the running exercises. Being in a group of their own,
+// they don't get the Ctrl-C meant for rustlings, so rustlings has to kill them
+// before it exits. Slots hold a process group ID or 0 when free. They are
+// atomics so that the signal handler can read them.
+#[cfg(unix)]
+static RUNNING_GROUPS: [AtomicI32; 256] = [const { AtomicI32::new(0) }; 256];
+
+// Takes a slot in `RUNNING_GROUPS` until dropped. With every slot taken the
+// group isn't tracked, which only matters if rustlings is interrupted.
+#[cfg(unix)]
+struct RunningGroup(Option<&'static AtomicI32>);
+
+#[cfg(unix)]
+impl RunningGroup {
+    fn track(child: &process::Child) -> Self {
+        let group = child.id() as i32;
+        RunningGroup(RUNNING_GROUPS.iter().find(|slot| {
+            slot.compare_exchange(0, group, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+        }))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RunningGroup {
+    fn drop(&mut self) {
+        if let Some(slot) = self.0 {
+            slot.store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+// Kill the running exercises when rustlings is interrupted or terminated, then
+// let the signal end rustlings as usual
+#[cfg(unix)]
+pub fn kill_exercises_on_exit() {
+    extern "C" fn handle(signal: libc::c_int) {
+        for slot in &RUNNING_GROUPS {
+            let group = slot.load(Ordering::SeqCst);
+            if group != 0 {
+                // SAFETY: kill is async-signal-safe
+                unsafe {
+                    libc::kill(-group, libc::SIGKILL);
+                }
+            }
+        }
+        // SAFETY: signal and raise are async-signal-safe
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the handler only calls async-signal-safe functions
+        unsafe {
+            libc::signal(signal, handle as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_exercises_on_exit() {}
+
+#[cfg(target_os = "linux")]
+fn set_rlimits(command: &mut Command, limits: Limits) {
+    use std::os::unix::process::CommandExt;
+
+    // glibc and uClibc have a type of their own for the resources, musl uses `c_int`
+    #[cfg(any(target_env = "gnu", target_env = "uclibc"))]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(any(target_env = "gnu", target_env = "uclibc")))]
+    type Resource = libc::c_int;
+
+    fn set(resource: Resource, value: u64) -> io::Result<()> {
+        let limit = libc::rlimit {
+            rlim_cur: value as libc::rlim_t,
+            rlim_max: value as libc::rlim_t,
+        };
+        // SAFETY: setrlimit only reads the struct passed to it
+        if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    if limits.memory_mb.is_none() && limits.cpu_secs.is_none() {
+        return;
+    }
+    // SAFETY: the closure runs in the forked child before `exec` and only
+    // calls setrlimit, which is async-signal-safe.
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(memory_mb) = limits.memory_mb {
+                set(libc::RLIMIT_AS, memory_mb * 1024 * 1024)?;
+            }
+            if let Some(cpu_secs) = limits.cpu_secs {
+                set(libc::RLIMIT_CPU, cpu_secs)?;
+            }
+            Ok(())
+        });
+    }
 }
 
 #[cfg(test)]
@@ -298,15 +1184,28 @@ mod test {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_check_clippy_is_not_cached() {
+        let dir = std::env::temp_dir().join(format!("rustlings_check_clippy_{}", std::process::id()));
+        let cache = BuildCache::new(&dir);
+        let mut exercise = Exercise::new("clippy", "tests/fixture/success/compSuccess.rs", Mode::Clippy);
+        exercise.cache = Some(cache.clone());
+        let key = cache.key(&exercise).unwrap();
+        let compiled = CompiledExercise {
+            exercise: &exercise,
+            outcome: None,
+            cache_key: Some(key.clone()),
+            handle: None,
+        };
+        assert!(compiled.check().is_ok());
+        assert!(cache.lookup(&exercise, &key).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_clean() {
         File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
+        let exercise = Exercise::new("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
@@ -314,12 +1213,7 @@ mod test {
 
     #[test]
     fn test_pending_state() {
-        let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::new("pending_exercise", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
 
         let state = exercise.state();
         let expected = vec![
@@ -355,25 +1249,178 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::new("finished_exercise", "tests/fixture/state/finished_exercise.rs", Mode::Compile);
 
         assert_eq!(exercise.state(), State::Done);
     }
 
     #[test]
     fn test_exercise_with_output() {
-        let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
-        };
+        let exercise = Exercise::new("exercise_with_output", "tests/fixture/success/testSuccess.rs", Mode::Test);
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_hidden_tests() {
+        // The exercise has no tests of its own, but the hidden ones still fail
+        let mut exercise = Exercise::new("sum", "tests/fixture/hidden_tests/sum.rs", Mode::Test);
+        exercise.hidden_tests = Some(PathBuf::from("tests/fixture/hidden_tests/hidden_tests/sum.rs"));
+        exercise.solution = Some(PathBuf::from("tests/fixture/hidden_tests/solutions/sum.rs"));
+        assert!(exercise.depends_on(Path::new("/home/me/tests/fixture/hidden_tests/hidden_tests/sum.rs")));
+        let out = exercise.compile().unwrap().run().unwrap_err();
+        assert_eq!(out.test_results().failed, ["rustlings_hidden_tests::adds"]);
+
+        let solved = exercise.solved().unwrap();
+        assert_eq!(solved.path, PathBuf::from("tests/fixture/hidden_tests/solutions/sum.rs"));
+        assert!(solved.compile().unwrap().run().is_ok());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "first"
+            path = "exercises/topic/first.rs"
+            mode = "compile"
+            hint = "Look closer."
+
+            [[exercises]]
+            name = "second"
+            path = "exercises/topic/second.rs"
+            mode = "test"
+            hint = ""
+            difficulty = "hard"
+            estimated_minutes = 15
+            prerequisites = ["first"]
+            book = ["https://doc.rust-lang.org/book/ch03-01-variables-and-mutability.html"]
+            tags = ["ownership"]
+            "#,
+        )
+        .unwrap();
+        assert!(list.validate_metadata().is_empty());
+
+        let (first, second) = (&list.exercises[0], &list.exercises[1]);
+        assert_eq!(first.topic(), "topic");
+        assert_eq!(first.metadata_summary(), None);
+        assert_eq!(first.full_hint(), "Look closer.");
+        assert_eq!(second.metadata.difficulty, Some(Difficulty::Hard));
+        assert_eq!(
+            second.metadata_summary().unwrap(),
+            "difficulty: hard | about 15 min | tags: ownership"
+        );
+        assert!(second.full_hint().contains("builds on: first"));
+    }
+
+    #[test]
+    fn test_hint_levels() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "first"
+            path = "exercises/topic/first.rs"
+            mode = "compile"
+            hint = ["Look closer.", "Read the error.", "Add `mut`."]
+            prerequisites = ["intro"]
+            "#,
+        )
+        .unwrap();
+        let exercise = &list.exercises[0];
+        assert_eq!(exercise.hint_levels(), 3);
+        assert_eq!(
+            exercise.hint(2),
+            "Hint 1/3: Look closer.\n\nHint 2/3: Read the error.\n\nThis exercise builds on: intro"
+        );
+        assert!(exercise.full_hint().contains("Hint 3/3: Add `mut`."));
+    }
+
+    #[test]
+    fn test_depends_on() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "tests7"
+            path = "exercises/tests/tests7.rs"
+            mode = "buildscript"
+            watch = ["exercises/tests/build.rs"]
+            hint = ""
+            "#,
+        )
+        .unwrap();
+        let exercise = &list.exercises[0];
+        assert!(exercise.depends_on(Path::new("/home/me/rustlings/exercises/tests/tests7.rs")));
+        assert!(exercise.depends_on(Path::new("/home/me/rustlings/exercises/tests/build.rs")));
+        assert!(!exercise.depends_on(Path::new("/home/me/rustlings/exercises/tests/tests8.rs")));
+    }
+
+    #[test]
+    fn test_load_tracks() {
+        let manifest = Path::new("tests/fixture/tracks/info.toml");
+        let names = |list: ExerciseList| list.exercises.into_iter().map(|e| e.name).collect::<Vec<_>>();
+
+        let list = ExerciseList::load(manifest, &[]).unwrap();
+        assert_eq!(list.exercises[2].metadata.prerequisites, vec!["extra/compSuccess"]);
+        assert!(list.validate_metadata().is_empty());
+        assert_eq!(
+            names(list),
+            vec!["compSuccess", "extra/compSuccess", "extra/testSuccess"]
+        );
+
+        let list = ExerciseList::load(manifest, &["main".to_string()]).unwrap();
+        assert_eq!(names(list), vec!["compSuccess"]);
+        assert!(ExerciseList::load(manifest, &["missing".to_string()])
+            .unwrap_err()
+            .contains("the tracks are: main, extra"));
+    }
+
+    #[test]
+    fn test_invalid_metadata() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "first"
+            path = "first.rs"
+            mode = "compile"
+            hint = ""
+            prerequisites = ["second", "missing"]
+            book = ["ch03-01"]
+            estimated_minutes = 0
+
+            [[exercises]]
+            name = "second"
+            path = "second.rs"
+            mode = "compile"
+            hint = ""
+            "#,
+        )
+        .unwrap();
+        assert_eq!(list.validate_metadata().len(), 4);
+    }
+
+    #[test]
+    fn test_test_results() {
+        let output = ExerciseOutput {
+            stdout: "running 3 tests\n\
+                     test tests::first ... ok\n\
+                     test tests::second ... FAILED\n\
+                     test tests::third ... ok\n\
+                     \n\
+                     test result: FAILED. 2 passed; 1 failed; 0 ignored\n"
+                .to_string(),
+            stderr: String::new(),
+            timed_out: false,
+        };
+        let results = output.test_results();
+        assert_eq!(
+            results,
+            TestResults {
+                passed: vec!["tests::first".into(), "tests::third".into()],
+                failed: vec!["tests::second".into()],
+            }
+        );
+        assert_eq!(results.to_string(), "2/3 tests passing");
+        assert_eq!(results.ratio(), Some(2.0 / 3.0));
+        assert_eq!(TestResults::default().ratio(), None);
+    }
 }
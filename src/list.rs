@@ -0,0 +1,305 @@
+use crate::exercise::{Difficulty, Exercise, Mode};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::str::FromStr;
+
+// The formats `rustlings list` can print the exercises in
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    // Aligned columns for humans, followed by the overall progress
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{s}`, expected table, json or csv")),
+        }
+    }
+}
+
+// What to sort the listed exercises by. Exercises that compare equal keep
+// the order of `info.toml`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SortKey {
+    Name,
+    // Pending exercises first
+    Status,
+    Topic,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortKey::Name),
+            "status" => Ok(SortKey::Status),
+            "topic" => Ok(SortKey::Topic),
+            _ => Err(format!("unknown sort key `{s}`, expected name, status or topic")),
+        }
+    }
+}
+
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pending,
+    Done,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Status::Pending => write!(f, "Pending"),
+            Status::Done => write!(f, "Done"),
+        }
+    }
+}
+
+// An exercise as it gets listed
+#[derive(Serialize, Debug)]
+pub struct Entry<'a> {
+    pub name: &'a str,
+    pub path: String,
+    pub topic: String,
+    pub mode: Mode,
+    pub difficulty: Option<Difficulty>,
+    pub status: Status,
+}
+
+impl<'a> Entry<'a> {
+    pub fn new(exercise: &'a Exercise, done: bool) -> Self {
+        Entry {
+            name: &exercise.name,
+            path: exercise.path.display().to_string(),
+            topic: exercise.topic(),
+            mode: exercise.mode,
+            difficulty: exercise.metadata.difficulty,
+            status: if done { Status::Done } else { Status::Pending },
+        }
+    }
+}
+
+// The listed exercises of a topic, with how far along the whole topic is
+#[derive(Serialize, Debug)]
+pub struct Topic<'a> {
+    pub topic: String,
+    pub done: usize,
+    pub total: usize,
+    pub percentage: f64,
+    pub exercises: Vec<&'a Entry<'a>>,
+}
+
+pub fn sort(entries: &mut [Entry], key: SortKey) {
+    match key {
+        SortKey::Name => entries.sort_by(|a, b| a.name.cmp(b.name)),
+        SortKey::Status => entries.sort_by_key(|e| e.status),
+        SortKey::Topic => entries.sort_by(|a, b| a.topic.cmp(&b.topic)),
+    }
+}
+
+// Group the listed exercises by topic, in the order the topics first show up.
+// The completion of a topic counts all of its exercises in `all`, not only
+// the listed ones.
+pub fn group<'a>(listed: &'a [Entry<'a>], all: &[Entry]) -> Vec<Topic<'a>> {
+    let mut topics: Vec<Topic> = vec![];
+    for entry in listed {
+        match topics.iter_mut().find(|t| t.topic == entry.topic) {
+            Some(topic) => topic.exercises.push(entry),
+            None => {
+                let in_topic = all.iter().filter(|e| e.topic == entry.topic);
+                let total = in_topic.clone().count();
+                let done = in_topic.filter(|e| e.status == Status::Done).count();
+                topics.push(Topic {
+                    topic: entry.topic.clone(),
+                    done,
+                    total,
+                    percentage: percentage(done, total),
+                    exercises: vec![entry],
+                });
+            }
+        }
+    }
+    topics
+}
+
+fn percentage(done: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (done as f64 / total as f64 * 1000.0).round() / 10.0
+}
+
+// Render the listed exercises, grouped by topic if `grouped`. Grouped CSV is
+// the completion of each topic without the exercises.
+pub fn render(listed: &[Entry], all: &[Entry], format: Format, grouped: bool) -> String {
+    match (format, grouped) {
+        (Format::Table, false) => {
+            let mut out = table_header();
+            table_rows(&mut out, listed);
+            out
+        }
+        (Format::Table, true) => {
+            let mut out = table_header();
+            for topic in group(listed, all) {
+                let name = if topic.topic.is_empty() { "-" } else { &topic.topic };
+                let _ = writeln!(
+                    out,
+                    "\n{name} ({}/{} done, {:.1} %)",
+                    topic.done, topic.total, topic.percentage
+                );
+                table_rows(&mut out, topic.exercises);
+            }
+            out
+        }
+        // Serializing these types can't fail
+        (Format::Json, false) => serde_json::to_string_pretty(listed).unwrap() + "\n",
+        (Format::Json, true) => serde_json::to_string_pretty(&group(listed, all)).unwrap() + "\n",
+        (Format::Csv, false) => {
+            let mut csv = String::from("name,path,topic,mode,difficulty,status\n");
+            for e in listed {
+                let mode = e.mode.to_string();
+                let difficulty = e.difficulty.map_or_else(String::new, |d| d.to_string());
+                let fields = [e.name, &e.path, &e.topic, &mode, &difficulty, &e.status.to_string()];
+                csv_row(&mut csv, &fields);
+            }
+            csv
+        }
+        (Format::Csv, true) => {
+            let mut csv = String::from("topic,done,total,percentage\n");
+            for topic in group(listed, all) {
+                let numbers = [topic.done.to_string(), topic.total.to_string(), topic.percentage.to_string()];
+                csv_row(&mut csv, &[&topic.topic, &numbers[0], &numbers[1], &numbers[2]]);
+            }
+            csv
+        }
+    }
+}
+
+fn table_header() -> String {
+    format!(
+        "{:<17}\t{:<46}\t{:<17}\t{:<10}\t{:<7}\n",
+        "Name", "Path", "Topic", "Difficulty", "Status"
+    )
+}
+
+fn table_rows<'a>(out: &mut String, entries: impl IntoIterator<Item = &'a Entry<'a>>) {
+    for e in entries {
+        let difficulty = e.difficulty.map_or_else(String::new, |d| d.to_string());
+        let _ = writeln!(
+            out,
+            "{:<17}\t{:<46}\t{:<17}\t{difficulty:<10}\t{:<7}",
+            e.name,
+            e.path,
+            e.topic,
+            e.status.to_string()
+        );
+    }
+}
+
+// Quote the fields that need it, as in RFC 4180
+fn csv_row(csv: &mut String, fields: &[&str]) {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    csv.push_str(&fields.join(","));
+    csv.push('\n');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    fn exercises() -> ExerciseList {
+        toml::from_str(
+            r#"
+            [[exercises]]
+            name = "intro2"
+            path = "exercises/intro/intro2.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "intro1"
+            path = "exercises/intro/intro1.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "tests1"
+            path = "exercises/tests/tests1.rs"
+            mode = "test"
+            hint = ""
+            topic = "testing, \"unit\""
+            difficulty = "easy"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sort_and_group() {
+        let list = exercises();
+        let all = list
+            .exercises
+            .iter()
+            .enumerate()
+            .map(|(i, e)| Entry::new(e, i == 1))
+            .collect::<Vec<_>>();
+        let names = |entries: &[Entry]| entries.iter().map(|e| e.name.to_string()).collect::<Vec<_>>();
+
+        let mut listed = list.exercises.iter().map(|e| Entry::new(e, false)).collect::<Vec<_>>();
+        sort(&mut listed, SortKey::Name);
+        assert_eq!(names(&listed), ["intro1", "intro2", "tests1"]);
+        let mut listed = list
+            .exercises
+            .iter()
+            .enumerate()
+            .map(|(i, e)| Entry::new(e, i == 1))
+            .collect::<Vec<_>>();
+        sort(&mut listed, SortKey::Status);
+        assert_eq!(names(&listed), ["intro2", "tests1", "intro1"]);
+
+        // Only the listed exercises are grouped, but all of them count
+        let topics = group(&all[..1], &all);
+        assert_eq!(topics.len(), 1);
+        assert_eq!((topics[0].done, topics[0].total, topics[0].percentage), (1, 2, 50.0));
+        assert_eq!(topics[0].exercises.len(), 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let list = exercises();
+        let all = list.exercises.iter().map(|e| Entry::new(e, false)).collect::<Vec<_>>();
+        assert_eq!(
+            render(&all[2..], &all, Format::Csv, false),
+            "name,path,topic,mode,difficulty,status\n\
+             tests1,exercises/tests/tests1.rs,\"testing, \"\"unit\"\"\",test,easy,Pending\n"
+        );
+        assert_eq!(
+            render(&all, &all, Format::Csv, true),
+            "topic,done,total,percentage\nintro,0,2,0\n\"testing, \"\"unit\"\"\",0,1,0\n"
+        );
+        assert!(render(&all, &all, Format::Table, true).contains("\nintro (0/2 done, 0.0 %)\nintro2 "));
+
+        let json: serde_json::Value = serde_json::from_str(&render(&all, &all, Format::Json, true)).unwrap();
+        assert_eq!(json[1]["exercises"][0]["mode"], "test");
+        assert_eq!(json[1]["exercises"][0]["status"], "pending");
+    }
+}
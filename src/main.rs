@@ -1,31 +1,47 @@
-use crate::exercise::{Exercise, ExerciseList};
-use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
+use crate::cache::{BuildCache, CACHE_DIR};
+use crate::check_info::check_info;
+use crate::cicv::{grade_isolated, ExerciseCheckList};
+use crate::exercise::{
+    BuildDir, Exercise, ExerciseList, Limits, Mode, CARGO_WORKSPACE_DIR, VENDOR_DIR,
+};
+use crate::progress::{ProgressState, STATE_FILE};
+use crate::project::{RustAnalyzerProject, PROJECT_FILE};
+use crate::report::Format;
+use crate::run::run;
+use crate::select::Selection;
 use crate::verify::verify;
+use crate::watcher::WatchOptions;
 use argh::FromArgs;
 use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io::{self, prelude::*, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::time::{UNIX_EPOCH, SystemTime};
+use tokio::sync::Semaphore;
 
 #[macro_use]
 mod ui;
 
+mod cache;
+mod check_info;
+mod cicv;
 mod exercise;
+mod list;
+mod progress;
 mod project;
+mod report;
+mod reset;
 mod run;
+mod select;
+mod tui;
 mod verify;
+mod watcher;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +55,27 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// how many seconds an exercise may run before it is killed,
+    /// unless it sets its own `timeout` in info.toml (default: 60)
+    #[argh(option)]
+    timeout: Option<u64>,
+    /// maximum memory in MiB an exercise may use while running (Linux only)
+    #[argh(option)]
+    memory_limit: Option<u64>,
+    /// maximum CPU seconds an exercise may use while running (Linux only)
+    #[argh(option)]
+    cpu_limit: Option<u64>,
+    /// consider exercises done once their `I AM NOT DONE` comment is removed,
+    /// instead of once they passed
+    #[argh(switch)]
+    legacy_marker: bool,
+    /// rebuild and rerun exercises even if they didn't change since their last run
+    #[argh(switch)]
+    no_cache: bool,
+    /// only work on the exercises of this track, can be given several times
+    /// (default: all tracks of info.toml and the manifests it includes)
+    #[argh(option)]
+    track: Vec<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -53,17 +90,66 @@ enum Subcommands {
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    CicvVerify(CicvVerifyArgs),
+    CheckInfo(CheckInfoArgs),
+    Vendor(VendorArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "cicvverify", description = "cicvverify")]
-struct CicvVerifyArgs {}
+struct CicvVerifyArgs {
+    #[argh(option, short = 'j')]
+    /// how many exercises to grade at the same time,
+    /// defaults to the number of available CPUs
+    jobs: Option<usize>,
+    #[argh(option)]
+    /// where to write the results, can be repeated,
+    /// defaults to .github/result/check_result.json
+    output: Vec<PathBuf>,
+    #[argh(option)]
+    /// the format of the results: json, junit, tap or markdown,
+    /// defaults to the one matching the extension of the output
+    format: Option<Format>,
+    #[argh(switch)]
+    /// report how many hints of each exercise were used, as recorded
+    /// in .rustlings-state.json, so only where the learner worked
+    hints: bool,
+    #[argh(option, short = 'f')]
+    /// only grade the matching exercises, see `rustlings list --help`
+    filter: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-info")]
+/// Checks info.toml against the exercises, which must all be unsolved
+struct CheckInfoArgs {
+    #[argh(option, short = 'j')]
+    /// how many exercises to compile at the same time,
+    /// defaults to the number of available CPUs
+    jobs: Option<usize>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "vendor")]
+/// Downloads the dependencies of the cargo exercises, so that they build offline
+struct VendorArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(switch)]
+    /// check all exercises, even after one fails, and sum up
+    /// which ones fail by topic
+    keep_going: bool,
+    #[argh(option)]
+    /// write a JUnit report of all exercises to the given file,
+    /// implies --keep-going
+    report: Option<PathBuf>,
+    #[argh(option, short = 'f')]
+    /// only verify the matching exercises, see `rustlings list --help`
+    filter: Option<String>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -72,6 +158,18 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// use the line based watch mode even in a terminal
+    #[argh(switch)]
+    plain: bool,
+    /// milliseconds to wait after the last edit before checking the exercises (default 500)
+    #[argh(option, default = "500")]
+    debounce: u64,
+    /// ignore edits of files matching this gitignore style pattern, can be repeated
+    #[argh(option)]
+    ignore: Vec<String>,
+    /// poll for changes, for file systems that don't report them such as network drives
+    #[argh(switch)]
+    poll: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -79,17 +177,24 @@ struct WatchArgs {
 /// Runs/Tests a single exercise
 struct RunArgs {
     #[argh(positional)]
-    /// the name of the exercise
+    /// the name of the exercise, or the exercises to run one after
+    /// the other, see `rustlings list --help`
     name: String,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Restores exercises to their original content, keeping a backup of your version
 struct ResetArgs {
     #[argh(positional)]
-    /// the name of the exercise
-    name: String,
+    /// the name of the exercise, or the name of a topic directory
+    name: Option<String>,
+    #[argh(switch)]
+    /// reset all exercises
+    all: bool,
+    #[argh(switch)]
+    /// restore your version of the exercises reset last
+    undo: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -104,7 +209,11 @@ struct HintArgs {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
-struct LspArgs {}
+struct LspArgs {
+    #[argh(switch)]
+    /// don't write rust-project.json, fail if it is out of date instead
+    check: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
@@ -117,8 +226,9 @@ struct ListArgs {
     /// show only the names of the exercises
     names: bool,
     #[argh(option, short = 'f')]
-    /// provide a string to match exercise names
-    /// comma separated patterns are acceptable
+    /// display only the matching exercises: comma separated names,
+    /// topics, parts of names or paths, globs like `algorithm*` and
+    /// ranges like `iterators1..iterators5`, or `re:<regex>`
     filter: Option<String>,
     #[argh(switch, short = 'u')]
     /// display only exercises not yet solved
@@ -126,32 +236,26 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseCheckList {
-    pub exercises: Vec<ExerciseResult>,
-    pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseResult {
-    pub name: String,
-    pub result: bool
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
-    pub total_exercations: usize,
-    pub total_succeeds: usize,
-    pub total_failures: usize,
-    pub total_time: u32,
+    #[argh(option)]
+    /// display only exercises with the given mode:
+    /// compile, test, clippy, buildscript or cargo
+    mode: Option<Mode>,
+    #[argh(option, default = "list::Format::Table")]
+    /// the format to list the exercises in: table, json or csv
+    format: list::Format,
+    #[argh(option)]
+    /// sort the exercises by name, status or topic instead of
+    /// listing them in the recommended order
+    sort: Option<list::SortKey>,
+    #[argh(switch, short = 'g')]
+    /// group the exercises by topic, with the completion of each topic
+    group: bool,
 }
 
 #[tokio::main]
 async fn main() {
     let args: Args = argh::from_env();
+    exercise::kill_exercises_on_exit();
 
     if args.version {
         println!("v{VERSION}");
@@ -178,171 +282,321 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    let exercise_list = ExerciseList::load(Path::new("info.toml"), &args.track).unwrap_or_else(|e| {
+        println!("{e}");
+        std::process::exit(1);
+    });
+    // `check-info` reports these along with everything else it finds
+    let checking_info = matches!(args.nested, Some(Subcommands::CheckInfo(_)));
+    let problems = exercise_list.validate_metadata();
+    if !problems.is_empty() && !checking_info {
+        println!("info.toml contains invalid exercise metadata:");
+        for problem in problems {
+            println!("  {problem}");
+        }
+        std::process::exit(1);
+    }
+    let mut exercises = exercise_list.exercises;
     let verbose = args.nocapture;
 
+    let limits = Limits {
+        timeout: args.timeout.map_or(Limits::default().timeout, Duration::from_secs),
+        memory_mb: args.memory_limit,
+        cpu_secs: args.cpu_limit,
+    };
+    let cache = (!args.no_cache).then(|| BuildCache::new(CACHE_DIR));
+    for exercise in &mut exercises {
+        exercise.limits = limits;
+        exercise.cache = cache.clone();
+    }
+    let mut progress_state = ProgressState::load(args.legacy_marker);
+
     let command = args.nested.unwrap_or_else(|| {
         println!("{DEFAULT_OUT}\n");
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+            if (subargs.paths || subargs.names) && subargs.format != list::Format::Table {
+                println!("`--paths` and `--names` can't be combined with `--format`.");
+                std::process::exit(1);
             }
-            let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
+            let all = exercises
+                .iter()
+                .map(|e| list::Entry::new(e, progress_state.is_done(e)))
+                .collect::<Vec<_>>();
+            let selected = match &subargs.filter {
+                Some(filter) => select_exercises(filter, &exercises),
+                None => exercises.iter().collect(),
+            };
+            let mut listed = selected
+                .into_iter()
+                .filter(|e| {
+                    let done = progress_state.is_done(e);
+                    (done && subargs.solved)
+                        || (!done && subargs.unsolved)
                         || (!subargs.solved && !subargs.unsolved)
+                })
+                .filter(|e| subargs.mode.is_none_or(|mode| e.mode == mode))
+                .map(|e| list::Entry::new(e, progress_state.is_done(e)))
+                .collect::<Vec<_>>();
+            if let Some(key) = subargs.sort {
+                list::sort(&mut listed, key);
+            }
+
+            let output = if subargs.paths {
+                listed.iter().map(|e| format!("{}\n", e.path)).collect()
+            } else if subargs.names {
+                listed.iter().map(|e| format!("{}\n", e.name)).collect()
+            } else {
+                list::render(&listed, &all, subargs.format, subargs.group)
+            };
+            // Somehow using println! leads to the binary panicking
+            // when its output is piped.
+            // So, we're handling a Broken Pipe error and exiting with 0 anyway
+            std::io::stdout().lock().write_all(output.as_bytes()).unwrap_or_else(|e| {
+                match e.kind() {
+                    std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                    _ => std::process::exit(1),
                 };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
-                }
             });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
+            // Anything else would get in the way of scripts reading the list
+            if subargs.format == list::Format::Table {
+                let exercises_done = all.iter().filter(|e| e.status == list::Status::Done).count();
+                let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+                println!(
+                    "Progress: You completed {} / {} exercises ({:.1} %).",
+                    exercises_done,
+                    exercises.len(),
+                    percentage_progress
+                );
+            }
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            let selected = select::find_all(&subargs.name, &exercises, &progress_state)
+                .unwrap_or_else(|e| {
+                    println!("{e}");
+                    std::process::exit(1)
+                });
+            for exercise in selected {
+                run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+                progress_state.record_pass(exercise);
+            }
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+            let result = match (subargs.undo, subargs.all, &subargs.name) {
+                (true, _, _) => reset::undo(),
+                (false, true, _) => reset::reset(&exercises.iter().collect::<Vec<_>>()),
+                (false, false, Some(name)) => {
+                    let mut selected = reset::select(name, &exercises);
+                    if selected.is_empty() {
+                        selected.push(find_exercise(name, &exercises, &progress_state));
+                    }
+                    reset::reset(&selected)
+                }
+                (false, false, None) => {
+                    println!("Tell me what to reset: an exercise, a topic or `--all`.");
+                    Err(())
+                }
+            };
+            result.unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &progress_state);
 
-            println!("{}", exercise.hint);
+            show_hint(exercise, &mut progress_state);
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Verify(subargs) => {
+            let exercises = narrow(exercises, subargs.filter.as_deref());
+            if !subargs.keep_going && subargs.report.is_none() {
+                verify(&exercises, (0, exercises.len()), &mut progress_state, verbose, false)
+                    .unwrap_or_else(|_| std::process::exit(1));
+            } else {
+                let start = Instant::now();
+                let results = verify::grade_all(&exercises, &mut progress_state);
+                verify::print_summary(&results);
+                let results = ExerciseCheckList::new(results, start.elapsed().as_secs() as u32);
+                if let Some(report) = subargs.report {
+                    if let Err(e) = report::write(&results, Format::Junit, &report) {
+                        println!("Failed to write the report to {}: {e}", report.display());
+                        std::process::exit(1);
+                    }
+                }
+                if results.statistics.total_failures > 0 {
+                    std::process::exit(1);
+                }
+            }
         }
 
-        Subcommands::CicvVerify(_subargs) => {
-            // let toml_str = &fs::read_to_string("info.toml").unwrap();
-            // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+        Subcommands::CicvVerify(subargs) => {
+            let exercises = narrow(exercises, subargs.filter.as_deref());
+            let outputs = report::outputs(&subargs.output, subargs.format).unwrap_or_else(|e| {
+                println!("{e}");
+                std::process::exit(1);
+            });
             let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let rights = Arc::new(Mutex::new(0));
             let alls = exercises.len();
-
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
-                    }
-                }
-            ));
+            let jobs = subargs.jobs.unwrap_or_else(|| {
+                thread::available_parallelism().map_or(1, |n| n.get())
+            });
+            let permits = Arc::new(Semaphore::new(jobs.max(1)));
+            let scratch_root = Arc::new(
+                std::env::temp_dir().join(format!("rustlings_cicv_{}", std::process::id())),
+            );
+            // The state file isn't committed, so a fresh checkout, like the
+            // one graded in CI, knows nothing about the hints
+            if subargs.hints && !Path::new(STATE_FILE).exists() {
+                warn!("There is no {} here, so no hints are reported as used", STATE_FILE);
+            }
+            let hints_used = exercises
+                .iter()
+                .map(|e| subargs.hints.then(|| progress_state.hints_used(e)))
+                .collect::<Vec<_>>();
 
             let mut tasks = vec![];
             for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                let inner_exercise = exercise;
                 let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
-                        }
+                let permits = Arc::clone(&permits);
+                let scratch_root = Arc::clone(&scratch_root);
+                let t = tokio::task::spawn(async move {
+                    let _permit = permits.acquire_owned().await.unwrap();
+                    let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                    let result = tokio::task::spawn_blocking(move || {
+                        grade_isolated(&exercise, &scratch_root)
+                    })
+                    .await
+                    .unwrap();
+                    if result.result {
+                        *c_mutex.lock().unwrap() += 1;
+                        println!("{}执行成功", result.name);
+                    } else if result.score > 0.0 {
+                        println!("{}执行失败 ({})", result.name, result.tests);
+                    } else {
+                        println!("{}执行失败", result.name);
                     }
+                    println!("总的题目数: {}", alls);
+                    println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                    let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                    println!("当前修改试卷耗时: {} s", now_end - now_start);
+                    result
                 });
                 tasks.push(t);
             }
-            for task in tasks { task.await.unwrap(); }
+            // Awaiting the tasks in order keeps the results in `info.toml`
+            // order, no matter which exercise finished first.
+            let mut results = vec![];
+            for (task, hints_used) in tasks.into_iter().zip(hints_used) {
+                let mut result = task.await.unwrap();
+                result.hints_used = hints_used;
+                results.push(result);
+            }
+            let _ignored = fs::remove_dir_all(&*scratch_root);
+
             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let total_time = now_end - now_start;
             println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
-            let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
-            fs::write(".github/result/check_result.json", serialized).unwrap();
+            let exercise_check_list = ExerciseCheckList::new(results, total_time as u32);
+            let mut write_failed = false;
+            for (path, format) in outputs {
+                if let Err(e) = report::write(&exercise_check_list, format, &path) {
+                    println!("Failed to write the results to {}: {e}", path.display());
+                    write_failed = true;
+                }
+            }
+            if write_failed {
+                std::process::exit(1);
+            }
         },
 
-        Subcommands::Lsp(_subargs) => {
+        Subcommands::CheckInfo(subargs) => {
+            let jobs = subargs.jobs.unwrap_or_else(|| {
+                thread::available_parallelism().map_or(1, |n| n.get())
+            });
+            let exercise_list = ExerciseList {
+                track: None,
+                include: vec![],
+                exercises,
+            };
+            let problems = check_info(&exercise_list, Path::new("exercises"), jobs);
+            if problems.is_empty() {
+                success!("Checked {} exercises, no problems found", exercise_list.exercises.len());
+                std::process::exit(0);
+            }
+            println!("Found {} problems:", problems.len());
+            for problem in problems {
+                println!("  {problem}");
+            }
+            std::process::exit(1);
+        }
+
+        Subcommands::Vendor(_subargs) => {
+            let cargo_exercises = exercises
+                .iter()
+                .filter(|e| e.mode == Mode::Cargo)
+                .collect::<Vec<_>>();
+            if cargo_exercises.is_empty() {
+                println!("There are no cargo exercises to vendor dependencies for.");
+                std::process::exit(0);
+            }
+            // Start from a clean workspace, so that exercises which are gone
+            // don't pull in their dependencies
+            let workspace = Path::new(CARGO_WORKSPACE_DIR);
+            let _ignored = fs::remove_dir_all(workspace.join("exercises"));
+            for exercise in cargo_exercises {
+                if let Err(e) = exercise.write_cargo_project(&BuildDir::Shared) {
+                    warn!("Failed to generate the cargo project of {}", format!("{exercise}: {e}"));
+                    std::process::exit(1);
+                }
+            }
+            // `cargo vendor` prints the configuration to use the vendored
+            // sources, which rustlings passes to cargo by itself
+            let status = Command::new("cargo")
+                .arg("vendor")
+                .arg("--manifest-path")
+                .arg(workspace.join("Cargo.toml"))
+                .arg(VENDOR_DIR)
+                .stdout(Stdio::null())
+                .status();
+            match status {
+                Ok(status) if status.success() => {
+                    success!("Vendored the dependencies into {}", VENDOR_DIR)
+                }
+                _ => {
+                    warn!("Failed to vendor the dependencies into {}", VENDOR_DIR);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Lsp(subargs) => {
             let mut project = RustAnalyzerProject::new();
             project
                 .get_sysroot_src()
                 .expect("Couldn't find toolchain path, do you have `rustc` installed?");
             project
-                .exercises_to_json()
+                .exercises_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
             if project.crates.is_empty() {
                 println!("Failed find any exercises, make sure you're in the `rustlings` folder");
+            } else if subargs.check {
+                match project.is_up_to_date() {
+                    Ok(true) => success!("{} is up to date", PROJECT_FILE),
+                    Ok(false) => {
+                        warn!("{} is out of date, run `rustlings lsp` to regenerate it", PROJECT_FILE);
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        println!("Failed to read {PROJECT_FILE}: {e}");
+                        std::process::exit(1);
+                    }
+                }
             } else if project.write_to_disk().is_err() {
                 println!("Failed to write rust-project.json to disk for rust-analyzer");
             } else {
@@ -351,57 +605,80 @@ async fn main() {
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
-                std::process::exit(1);
-            }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+        Subcommands::Watch(subargs) => {
+            let options = WatchOptions {
+                debounce: Duration::from_millis(subargs.debounce),
+                ignore: subargs.ignore,
+                poll: subargs.poll,
+            };
+            let result = if subargs.plain || !io::stdout().is_terminal() {
+                watch(&exercises, &mut progress_state, &options, verbose, subargs.success_hints)
+            } else {
+                watcher::watch(&exercises, &options).and_then(|(_watcher, events)| {
+                    tui::watch(&exercises, &mut progress_state, &events, subargs.success_hints)
+                        .map_err(notify::Error::io)
+                })
+            };
+            match result {
+                Err(e) => {
+                    println!(
+                        "Error: Could not watch your progress. Error message was {:?}.",
+                        e
+                    );
+                    println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                    println!("Running `rustlings watch --poll` may help with the latter.");
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} All exercises completed! {emoji}",
+                        emoji = Emoji("🎉", "★")
+                    );
+                    println!("\n{FENISH_LINE}\n");
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("We hope you're enjoying learning about Rust!");
+                    println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+                }
             }
-        },
+        }
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
+// A command typed into the watch mode shell, handled by the watch loop
+enum WatchCommand {
+    Hint,
+    Skip,
+    Goto(String),
+    Prev,
+    List,
+    Reset,
+    Quit,
+}
+
+fn spawn_watch_shell(commands: Sender<WatchCommand>) {
     println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
+            // Nothing more to read
+            Ok(0) => break,
             Ok(_) => {
                 let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
+                if input == "clear" {
                     println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
                 } else if input.eq("help") {
                     println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
+                    println!("  hint        - prints the current exercise's hints, one more each time");
+                    println!("  skip        - parks the current exercise and moves on to the next one");
+                    println!("  goto <name> - continues with the given exercise");
+                    println!("  prev        - goes back to the last unsolved exercise before this one");
+                    println!("  list        - lists the exercises and whether they are done");
+                    println!("  reset       - restores the original version of the current exercise");
+                    println!("  clear       - clears the screen");
+                    println!("  quit        - quits watch mode");
+                    println!("  !<cmd>      - executes a command, like `!rustc --explain E0381`");
+                    println!("  help        - displays this help message");
                     println!();
                     println!("Watch mode automatically re-evaluates the current exercise");
                     println!("when you edit a file's contents.")
@@ -413,7 +690,29 @@ fn spawn_watch_shell(
                         println!("failed to execute command `{}`: {}", cmd, e);
                     }
                 } else {
-                    println!("unknown command: {input}");
+                    let command = match input.split_whitespace().collect::<Vec<_>>()[..] {
+                        ["hint"] => WatchCommand::Hint,
+                        ["skip"] => WatchCommand::Skip,
+                        ["goto", name] => WatchCommand::Goto(name.to_string()),
+                        ["prev"] => WatchCommand::Prev,
+                        ["list"] => WatchCommand::List,
+                        ["reset"] => WatchCommand::Reset,
+                        ["quit"] => {
+                            println!("Bye!");
+                            WatchCommand::Quit
+                        }
+                        ["goto", ..] => {
+                            println!("usage: goto <exercise name>");
+                            continue;
+                        }
+                        _ => {
+                            println!("unknown command: {input}");
+                            continue;
+                        }
+                    };
+                    if commands.send(command).is_err() {
+                        break;
+                    }
                 }
             }
             Err(error) => println!("error reading command: {error}"),
@@ -421,24 +720,85 @@ fn spawn_watch_shell(
     });
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
-    if name.eq("next") {
-        exercises
-            .iter()
-            .find(|e| !e.looks_done())
-            .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
-                std::process::exit(1)
-            })
+// The exercises picked by `selection`, see `Selection`. Exits if it's invalid.
+fn select_exercises<'a>(selection: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+    selection
+        .parse::<Selection>()
+        .and_then(|selection| selection.select(exercises))
+        .unwrap_or_else(|e| {
+            println!("{e}");
+            std::process::exit(1)
+        })
+}
+
+// Only the exercises picked by `filter`, or all of them without one.
+// Exits if the filter doesn't pick any.
+fn narrow(exercises: Vec<Exercise>, filter: Option<&str>) -> Vec<Exercise> {
+    let Some(filter) = filter else {
+        return exercises;
+    };
+    let selected = select_exercises(filter, &exercises)
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if selected.is_empty() {
+        println!("No exercises match `{filter}`.");
+        std::process::exit(1);
+    }
+    selected
+}
+
+// Print the hints of the exercise seen so far plus the next one
+fn show_hint(exercise: &Exercise, progress_state: &mut ProgressState) {
+    let shown = progress_state.use_hint(exercise);
+    println!("{}", exercise.hint(shown));
+    let left = exercise.hint_levels() - shown;
+    if left > 0 {
+        println!();
+        println!(
+            "{left} more {} left, ask for a hint again to see the next one.",
+            if left == 1 { "hint" } else { "hints" }
+        );
+    }
+}
+
+// The exercise meant by `name`, see `select::find`. Exits if there is none.
+fn find_exercise<'a>(
+    name: &str,
+    exercises: &'a [Exercise],
+    progress_state: &ProgressState,
+) -> &'a Exercise {
+    select::find(name, exercises, progress_state).unwrap_or_else(|e| {
+        println!("{e}");
+        std::process::exit(1)
+    })
+}
+
+// The edited file, if it is a Rust source file or one of the exercises depends on it
+fn edited_file(path: PathBuf, exercises: &[Exercise]) -> Option<PathBuf> {
+    let relevant =
+        path.extension() == Some(OsStr::new("rs")) || exercises.iter().any(|e| e.depends_on(&path));
+    if relevant && path.exists() {
+        path.canonicalize().ok()
     } else {
-        exercises
+        None
+    }
+}
+
+// The exercises to check first after `path` was edited: the exercise it is
+// the source of, and the pending exercises that depend on it otherwise
+fn edited_exercises<'a>(
+    path: &Path,
+    exercises: &'a [Exercise],
+    progress_state: &ProgressState,
+) -> Vec<&'a Exercise> {
+    match exercises.iter().find(|e| path.ends_with(&e.path)) {
+        Some(edited) => vec![edited],
+        None => exercises
             .iter()
-            .find(|e| e.name == name)
-            .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
-                std::process::exit(1)
-            })
+            .filter(|e| e.depends_on(path))
+            .filter(|e| !progress_state.is_done(e) && !progress_state.is_skipped(e))
+            .collect(),
     }
 }
 
@@ -449,6 +809,8 @@ enum WatchStatus {
 
 fn watch(
     exercises: &[Exercise],
+    progress_state: &mut ProgressState,
+    options: &WatchOptions,
     verbose: bool,
     success_hints: bool,
 ) -> notify::Result<WatchStatus> {
@@ -458,66 +820,169 @@ fn watch(
         println!("\x1Bc");
     }
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
+    // The exercises watch mode still has to get through, in order
+    fn pending<'a>(exercises: &'a [Exercise], progress_state: &ProgressState) -> Vec<&'a Exercise> {
+        exercises
+            .iter()
+            .filter(|e| !progress_state.is_done(e) && !progress_state.is_skipped(e))
+            .collect()
+    }
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+    // Continue with the given exercises, then with the pending ones
+    fn starting_with<'a>(
+        first: Vec<&'a Exercise>,
+        exercises: &'a [Exercise],
+        progress_state: &ProgressState,
+    ) -> Vec<&'a Exercise> {
+        let rest = pending(exercises, progress_state)
+            .into_iter()
+            .filter(|e| !first.iter().any(|f| f.name == e.name))
+            .collect::<Vec<_>>();
+        first.into_iter().chain(rest).collect()
+    }
 
-    clear_screen();
+    let (_watcher, rx) = watcher::watch(exercises, options)?;
+    let (commands_tx, commands) = channel();
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    // The exercise the learner is working on, `None` if only skipped ones are left
+    let mut current: Option<&Exercise> = None;
+    // The exercises to verify before waiting for the next change
+    let mut to_verify = Some(
+        exercises
+            .iter()
+            .filter(|e| !progress_state.is_skipped(e))
+            .collect::<Vec<_>>(),
+    );
+    let mut shell_spawned = false;
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
+        if let Some(to_verify) = to_verify.take() {
+            let num_done = exercises
+                .iter()
+                .filter(|e| progress_state.is_done(e))
+                .filter(|e| !to_verify.iter().any(|v| v.name == e.name))
+                .count();
+            clear_screen();
+            match verify(
+                to_verify,
+                (num_done, exercises.len()),
+                progress_state,
+                verbose,
+                success_hints,
+            ) {
+                Ok(_) if exercises.iter().all(|e| progress_state.is_done(e)) => {
+                    return Ok(WatchStatus::Finished)
+                }
+                Ok(_) => {
+                    current = None;
+                    let skipped = exercises
+                        .iter()
+                        .filter(|e| !progress_state.is_done(e))
+                        .map(|e| e.name.as_str())
+                        .collect::<Vec<_>>();
+                    println!("Only the exercises you skipped are left: {}", skipped.join(", "));
+                    println!("Type `goto <name>` to get back to one of them.");
+                }
+                Err(exercise) => {
+                    current = Some(exercise);
+                    if let Some(summary) = exercise.metadata_summary() {
+                        println!("{} ({summary})", exercise.name);
                     }
                 }
-                _ => {}
-            },
+            }
+            if !shell_spawned {
+                spawn_watch_shell(commands_tx.clone());
+                shell_spawned = true;
+            }
+        }
+
+        match rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(Ok(paths)) => {
+                // Saving several files at once is handled like saving the last of them
+                if let Some(filepath) = paths.into_iter().rev().find_map(|p| edited_file(p, exercises)) {
+                    let edited = edited_exercises(&filepath, exercises, progress_state);
+                    to_verify = Some(starting_with(edited, exercises, progress_state));
+                }
+            }
+            Ok(Err(e)) => println!("watch error: {e}"),
             Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+                // the timeout expired, just check for commands below
             }
             Err(e) => println!("watch error: {e:?}"),
         }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
+
+        for command in commands.try_iter() {
+            let target = match command {
+                WatchCommand::Hint => {
+                    match current {
+                        Some(exercise) => show_hint(exercise, progress_state),
+                        None => println!("There is no exercise to give a hint for."),
+                    }
+                    continue;
+                }
+                WatchCommand::Skip => {
+                    match current {
+                        Some(exercise) => {
+                            progress_state.skip(exercise);
+                            to_verify = Some(pending(exercises, progress_state));
+                        }
+                        None => println!("There is no exercise to skip."),
+                    }
+                    continue;
+                }
+                WatchCommand::List => {
+                    for exercise in exercises {
+                        let status = if progress_state.is_done(exercise) {
+                            "Done"
+                        } else if progress_state.is_skipped(exercise) {
+                            "Skipped"
+                        } else {
+                            "Pending"
+                        };
+                        let marker = match current {
+                            Some(current) if current.name == exercise.name => "→",
+                            _ => " ",
+                        };
+                        println!("{marker} {:<20} {status}", exercise.name);
+                    }
+                    continue;
+                }
+                WatchCommand::Quit => return Ok(WatchStatus::Unfinished),
+                WatchCommand::Reset => {
+                    // Rewriting the exercise makes it get verified again
+                    match current {
+                        Some(exercise) => {
+                            let _ = reset::reset(&[exercise]);
+                        }
+                        None => println!("There is no exercise to reset."),
+                    }
+                    continue;
+                }
+                WatchCommand::Goto(name) => match exercises.iter().find(|e| e.name == name) {
+                    Some(exercise) => exercise,
+                    None => {
+                        println!("No exercise found for '{name}'!");
+                        continue;
+                    }
+                },
+                WatchCommand::Prev => {
+                    let before = current
+                        .and_then(|current| exercises.iter().position(|e| e.name == current.name))
+                        .unwrap_or(exercises.len());
+                    match exercises[..before].iter().rev().find(|e| !progress_state.is_done(e)) {
+                        Some(exercise) => exercise,
+                        None => {
+                            println!("There is no unsolved exercise before this one.");
+                            continue;
+                        }
+                    }
+                }
+            };
+            if progress_state.is_done(target) {
+                println!("{target} is already solved.");
+                continue;
+            }
+            progress_state.unskip(target);
+            to_verify = Some(starting_with(vec![target], exercises, progress_state));
         }
     }
 }
@@ -0,0 +1,237 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const STATE_FILE: &str = ".rustlings-state.json";
+
+// The exercises a learner has solved, persisted next to `info.toml`.
+// Unlike the `I AM NOT DONE` marker, an exercise only counts as done here
+// once rustlings itself saw it compile and pass.
+pub struct ProgressState {
+    path: PathBuf,
+    file: StateFile,
+    // Decide completion by the `I AM NOT DONE` marker instead
+    legacy_marker: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct StateFile {
+    exercises: BTreeMap<String, ExerciseProgress>,
+    // Exercises the learner parked in watch mode to come back to later
+    #[serde(default)]
+    skipped: BTreeSet<String>,
+    // How many hints of each exercise the learner has seen
+    #[serde(default)]
+    hints_used: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExerciseProgress {
+    // Seconds since the UNIX epoch when the exercise last passed
+    pub passed_at: u64,
+    // SHA-256 of the exercise source at that time
+    pub source_hash: String,
+}
+
+impl ProgressState {
+    // Load the state file from the current directory
+    pub fn load(legacy_marker: bool) -> Self {
+        Self::load_from(Path::new(STATE_FILE), legacy_marker)
+    }
+
+    // Load the state file at `path`, starting from scratch if there is none yet
+    pub fn load_from(path: &Path, legacy_marker: bool) -> Self {
+        let file = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                println!("Ignoring the invalid progress file {}: {e}", path.display());
+                StateFile::default()
+            }),
+            Err(_) => StateFile::default(),
+        };
+        ProgressState {
+            path: path.to_path_buf(),
+            file,
+            legacy_marker,
+        }
+    }
+
+    // Whether the exercise is solved. Without the legacy marker mode this
+    // means it passed and has not been edited since.
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        if self.legacy_marker {
+            exercise.looks_done()
+        } else {
+            self.has_passed(exercise)
+        }
+    }
+
+    // Whether the exercise passed in its current form
+    pub fn has_passed(&self, exercise: &Exercise) -> bool {
+        match (self.file.exercises.get(&exercise.name), exercise.source_hash()) {
+            (Some(progress), Ok(hash)) => progress.source_hash == hash,
+            _ => false,
+        }
+    }
+
+    pub fn is_skipped(&self, exercise: &Exercise) -> bool {
+        self.file.skipped.contains(&exercise.name)
+    }
+
+    // Park the exercise, so that watch mode moves on without it
+    pub fn skip(&mut self, exercise: &Exercise) {
+        if self.file.skipped.insert(exercise.name.clone()) {
+            self.save_or_warn();
+        }
+    }
+
+    pub fn unskip(&mut self, exercise: &Exercise) {
+        if self.file.skipped.remove(&exercise.name) {
+            self.save_or_warn();
+        }
+    }
+
+    // How many of its hints the learner has seen. Never more than the exercise
+    // has, even if it had more when the state was saved.
+    pub fn hints_used(&self, exercise: &Exercise) -> usize {
+        let used = self.file.hints_used.get(&exercise.name).copied().unwrap_or(0);
+        used.min(exercise.hint_levels())
+    }
+
+    // Reveal the next hint of the exercise, if there is one left. Returns how
+    // many of its hints the learner has seen now.
+    pub fn use_hint(&mut self, exercise: &Exercise) -> usize {
+        let used = self.hints_used(exercise);
+        if used >= exercise.hint_levels() {
+            return used;
+        }
+        self.file.hints_used.insert(exercise.name.clone(), used + 1);
+        self.save_or_warn();
+        used + 1
+    }
+
+    pub fn uses_legacy_marker(&self) -> bool {
+        self.legacy_marker
+    }
+
+    // Remember that the exercise passed in its current form
+    pub fn record_pass(&mut self, exercise: &Exercise) {
+        let source_hash = match exercise.source_hash() {
+            Ok(hash) => hash,
+            Err(_) => return,
+        };
+        if self.file.exercises.get(&exercise.name).map(|p| &p.source_hash) == Some(&source_hash) {
+            return;
+        }
+        // A solved exercise is no longer parked
+        self.file.skipped.remove(&exercise.name);
+        let passed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.file.exercises.insert(
+            exercise.name.clone(),
+            ExerciseProgress {
+                passed_at,
+                source_hash,
+            },
+        );
+        self.save_or_warn();
+    }
+
+    fn save_or_warn(&self) {
+        if let Err(e) = self.save() {
+            println!("Failed to save your progress to {}: {e}", self.path.display());
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&self.file)?;
+        // Write to a temporary file first, so that the state is never left
+        // half written if rustlings gets interrupted.
+        let tmp = self.path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(path: &Path) -> Exercise {
+        Exercise::new("example", path, Mode::Compile)
+    }
+
+    #[test]
+    fn test_record_pass() {
+        let dir = std::env::temp_dir().join(format!("rustlings_progress_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("example.rs");
+        fs::write(&source, "fn main() {}\n").unwrap();
+        let exercise = exercise(&source);
+
+        let mut state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        assert!(!state.is_done(&exercise));
+        state.record_pass(&exercise);
+        assert!(state.is_done(&exercise));
+
+        // The progress survives a reload, but not an edit of the exercise
+        let state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        assert!(state.is_done(&exercise));
+        fs::write(&source, "fn main() { }\n").unwrap();
+        assert!(!state.is_done(&exercise));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_skip() {
+        let dir = std::env::temp_dir().join(format!("rustlings_skip_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("example.rs");
+        fs::write(&source, "fn main() {}\n").unwrap();
+        let exercise = exercise(&source);
+
+        let mut state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        state.skip(&exercise);
+        let mut state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        assert!(state.is_skipped(&exercise));
+
+        // Solving a skipped exercise unparks it
+        state.record_pass(&exercise);
+        assert!(!state.is_skipped(&exercise));
+        state.skip(&exercise);
+        state.unskip(&exercise);
+        assert!(!ProgressState::load_from(&dir.join(STATE_FILE), false).is_skipped(&exercise));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_use_hint() {
+        let dir = std::env::temp_dir().join(format!("rustlings_hints_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut exercise = exercise(&dir.join("example.rs"));
+        exercise.hints = vec!["nudge".into(), "concept".into()];
+
+        let mut state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        assert_eq!(state.hints_used(&exercise), 0);
+        assert_eq!(state.use_hint(&exercise), 1);
+        let mut state = ProgressState::load_from(&dir.join(STATE_FILE), false);
+        assert_eq!(state.hints_used(&exercise), 1);
+        assert_eq!(state.use_hint(&exercise), 2);
+        // There is no third hint to reveal
+        assert_eq!(state.use_hint(&exercise), 2);
+
+        // Hints dropped from `info.toml` no longer count
+        exercise.hints.pop();
+        assert_eq!(state.hints_used(&exercise), 1);
+        assert_eq!(state.use_hint(&exercise), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
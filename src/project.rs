@@ -1,29 +1,48 @@
-use glob::glob;
+use crate::exercise::{Exercise, Mode, EDITION, VENDOR_DIR};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Where rust-analyzer looks for the project, at the root of the exercises
+pub const PROJECT_FILE: &str = "rust-project.json";
+
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
 pub struct RustAnalyzerProject {
+    sysroot: String,
     sysroot_src: String,
     pub crates: Vec<Crate>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    display_name: String,
     root_module: String,
     edition: String,
-    deps: Vec<String>,
+    deps: Vec<Dep>,
     cfg: Vec<String>,
+    is_workspace_member: bool,
+}
+
+/// A dependency on another crate of the project, by its index in `crates`
+#[derive(Serialize, Deserialize)]
+pub struct Dep {
+    #[serde(rename = "crate")]
+    krate: usize,
+    name: String,
 }
 
 impl RustAnalyzerProject {
     pub fn new() -> RustAnalyzerProject {
         RustAnalyzerProject {
+            sysroot: String::new(),
             sysroot_src: String::new(),
             crates: Vec::new(),
         }
@@ -31,48 +50,148 @@ impl RustAnalyzerProject {
 
     /// Write rust-project.json to disk
     pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
-        std::fs::write(
-            "./rust-project.json",
-            serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
-        )?;
-        Ok(())
+        let mut json = serde_json::to_string_pretty(&self).expect("Failed to serialize to JSON");
+        json.push('\n');
+        fs::write(PROJECT_FILE, json)
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        if let Some(ext) = path.extension() {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
+    /// Whether rust-project.json on disk describes the same project, so
+    /// that a file written by an older version of rustlings counts as out
+    /// of date. A missing file is out of date too.
+    pub fn is_up_to_date(&self) -> Result<bool, Box<dyn Error>> {
+        let on_disk = match fs::read_to_string(PROJECT_FILE) {
+            Ok(on_disk) => on_disk,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        let on_disk: serde_json::Value = serde_json::from_str(&on_disk)?;
+        Ok(on_disk == serde_json::to_value(self)?)
+    }
+
+    /// Add a crate to `rust-project.json` for every exercise in `info.toml`,
+    /// which allows rust-analyzer to treat it like it gets built by rustlings:
+    /// with the tests of the exercises that have them, the cfgs set by the
+    /// build script of build script exercises and the vendored dependencies
+    /// of cargo exercises
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+        for exercise in exercises {
+            let mut cfg = vec![];
+            if matches!(exercise.mode, Mode::Test | Mode::BuildScript | Mode::Cargo) {
+                // This allows rust_analyzer to work inside #[test] blocks
+                cfg.push("test".to_string());
+            }
+            if exercise.mode == Mode::BuildScript {
+                let build_script = exercise.path.with_file_name("build.rs");
+                if let Ok(source) = fs::read_to_string(&build_script) {
+                    cfg.extend(build_script_cfgs(&source));
+                }
+                self.build_script_to_json(&build_script);
             }
+            let deps = self.dependencies_to_json(&exercise.dependencies, &BTreeSet::new())?;
+            self.crates.push(Crate {
+                display_name: exercise.name.clone(),
+                root_module: exercise.path.display().to_string(),
+                edition: EDITION.to_string(),
+                deps,
+                cfg,
+                is_workspace_member: true,
+            });
         }
-
         Ok(())
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
+    /// Add a crate for a build script, unless another exercise shares it
+    fn build_script_to_json(&mut self, build_script: &Path) {
+        let root_module = build_script.display().to_string();
+        if self.crates.iter().any(|c| c.root_module == root_module) {
+            return;
         }
-        Ok(())
+        self.crates.push(Crate {
+            display_name: "build_script_build".to_string(),
+            root_module,
+            edition: EDITION.to_string(),
+            deps: Vec::new(),
+            cfg: Vec::new(),
+            is_workspace_member: true,
+        });
     }
 
-    /// Use `rustc` to determine the default toolchain
-    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
-        // check if RUST_SRC_PATH is set
-        if let Ok(path) = env::var("RUST_SRC_PATH") {
-            self.sysroot_src = path;
-            return Ok(());
+    /// Add the crates of the dependencies that `rustlings vendor` vendored,
+    /// skipping optional dependencies that no feature in `enabled` turns on.
+    /// Dependencies that aren't vendored are left out.
+    fn dependencies_to_json(
+        &mut self,
+        dependencies: &toml::value::Table,
+        enabled: &BTreeSet<String>,
+    ) -> Result<Vec<Dep>, Box<dyn Error>> {
+        let mut deps = vec![];
+        for (name, spec) in dependencies {
+            let optional = spec.get("optional").and_then(|o| o.as_bool()).unwrap_or(false);
+            if optional && !enabled.contains(name) && !enabled.contains(&format!("dep:{name}")) {
+                continue;
+            }
+            let package = spec.get("package").and_then(|p| p.as_str()).unwrap_or(name);
+            if let Some(krate) = self.vendored_to_json(package, spec)? {
+                deps.push(Dep {
+                    krate,
+                    name: name.replace('-', "_"),
+                });
+            }
+        }
+        Ok(deps)
+    }
+
+    /// Add the crate of a vendored package and of its dependencies, returning
+    /// its index. A package depended on more than once gets the features of
+    /// the first dependency on it, which is close enough for an editor.
+    fn vendored_to_json(
+        &mut self,
+        package: &str,
+        spec: &toml::Value,
+    ) -> Result<Option<usize>, Box<dyn Error>> {
+        let dir = Path::new(VENDOR_DIR).join(package);
+        let Ok(manifest) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            return Ok(None);
+        };
+        let manifest: toml::Value = toml::from_str(&manifest)?;
+        let lib = manifest
+            .get("lib")
+            .and_then(|lib| lib.get("path"))
+            .and_then(|path| path.as_str())
+            .unwrap_or("src/lib.rs");
+        let root_module = dir.join(lib).display().to_string();
+        if let Some(index) = self.crates.iter().position(|c| c.root_module == root_module) {
+            return Ok(Some(index));
         }
 
+        let features = enabled_features(&manifest, spec);
+        let deps = match manifest.get("dependencies").and_then(|d| d.as_table()) {
+            Some(dependencies) => self.dependencies_to_json(dependencies, &features)?,
+            None => Vec::new(),
+        };
+        let edition = manifest
+            .get("package")
+            .and_then(|package| package.get("edition"))
+            .and_then(|edition| edition.as_str())
+            .unwrap_or("2015");
+        self.crates.push(Crate {
+            display_name: package.to_string(),
+            root_module,
+            edition: edition.to_string(),
+            deps,
+            cfg: features
+                .iter()
+                .filter(|f| !f.starts_with("dep:"))
+                .map(|f| format!("feature=\"{f}\""))
+                .collect(),
+            is_workspace_member: false,
+        });
+        Ok(Some(self.crates.len() - 1))
+    }
+
+    /// Use `rustc` to determine the default toolchain, whose standard library
+    /// the exercises are linked against
+    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
         let toolchain = Command::new("rustc")
             .arg("--print")
             .arg("sysroot")
@@ -86,14 +205,133 @@ impl RustAnalyzerProject {
 
         println!("Determined toolchain: {}\n", &toolchain);
 
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
-            .join("lib")
-            .join("rustlib")
-            .join("src")
-            .join("rust")
-            .join("library")
-            .to_string_lossy())
-        .to_string();
+        self.sysroot = toolchain.to_string();
+        // check if RUST_SRC_PATH is set
+        self.sysroot_src = match env::var("RUST_SRC_PATH") {
+            Ok(path) => path,
+            Err(_) => PathBuf::from(toolchain)
+                .join("lib")
+                .join("rustlib")
+                .join("src")
+                .join("rust")
+                .join("library")
+                .to_string_lossy()
+                .to_string(),
+        };
         Ok(())
     }
 }
+
+/// The cfgs a build script sets with `cargo:rustc-cfg=...`. The build script
+/// isn't run, so only the cfgs spelled out in string literals are found.
+fn build_script_cfgs(source: &str) -> Vec<String> {
+    let cfg = Regex::new(r#"rustc-cfg=((?:[^"\\]|\\.)*)""#).unwrap();
+    cfg.captures_iter(source)
+        .map(|captures| captures[1].replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// The features of a vendored package that a dependency on it enables, along
+/// with the optional dependencies they turn on as `dep:<name>`
+fn enabled_features(manifest: &toml::Value, spec: &toml::Value) -> BTreeSet<String> {
+    let declared = manifest.get("features").and_then(|f| f.as_table());
+    let mut pending: Vec<String> = spec
+        .get("features")
+        .and_then(|f| f.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f.as_str().map(String::from))
+        .collect();
+    let default_features = spec.get("default-features").and_then(|d| d.as_bool());
+    if default_features != Some(false) && declared.is_some_and(|d| d.contains_key("default")) {
+        pending.push("default".to_string());
+    }
+
+    let mut enabled = BTreeSet::new();
+    while let Some(feature) = pending.pop() {
+        // A feature of a dependency turns the dependency on, unless it's `dep?/feature`
+        if let Some((dependency, _)) = feature.split_once('/') {
+            if !dependency.ends_with('?') {
+                enabled.insert(format!("dep:{dependency}"));
+            }
+            continue;
+        }
+        if !enabled.insert(feature.clone()) {
+            continue;
+        }
+        if let Some(implied) = declared.and_then(|d| d.get(&feature)).and_then(|i| i.as_array()) {
+            pending.extend(implied.iter().filter_map(|i| i.as_str().map(String::from)));
+        }
+    }
+    enabled
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exercises_to_json() {
+        // Not the build script in exercises/, which learners edit
+        let dir = env::temp_dir().join(format!("rustlings_project_{}", std::process::id()));
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        let build_script = dir.join("tests").join("build.rs");
+        fs::write(&build_script, "fn main() {\n    println!(\"cargo:rustc-cfg=feature=\\\"pass\\\"\");\n}\n")
+            .unwrap();
+        let exercises = [
+            Exercise::new("intro1", "exercises/intro/intro1.rs", Mode::Compile),
+            Exercise::new("tests1", "exercises/tests/tests1.rs", Mode::Test),
+            Exercise::new("tests7", dir.join("tests").join("tests7.rs"), Mode::BuildScript),
+            Exercise::new("tests8", dir.join("tests").join("tests8.rs"), Mode::BuildScript),
+        ];
+        let mut project = RustAnalyzerProject::new();
+        project.exercises_to_json(&exercises).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let crates = serde_json::to_value(&project.crates).unwrap();
+        assert_eq!(crates[0]["root_module"], "exercises/intro/intro1.rs");
+        assert_eq!(crates[0]["cfg"], serde_json::json!([]));
+        assert_eq!(crates[1]["cfg"], serde_json::json!(["test"]));
+        // The build script comes before the first exercise it builds, only once
+        assert_eq!(crates[2]["root_module"], build_script.display().to_string());
+        assert_eq!(crates[3]["display_name"], "tests7");
+        assert_eq!(crates[3]["cfg"], serde_json::json!(["test", "feature=\"pass\""]));
+        assert_eq!(crates[4]["display_name"], "tests8");
+        assert_eq!(crates[4]["cfg"], crates[3]["cfg"]);
+        assert_eq!(project.crates.len(), 5);
+    }
+
+    #[test]
+    fn test_build_script_cfgs() {
+        let source = r#"
+            println!("cargo:rustc-cfg=feature=\"pass\"");
+            let cfg = "rustc-cfg=tests_pass";
+            println!("cargo:rustc-env=TEST_FOO={timestamp}");
+        "#;
+        assert_eq!(build_script_cfgs(source), ["feature=\"pass\"", "tests_pass"]);
+    }
+
+    #[test]
+    fn test_enabled_features() {
+        let manifest: toml::Value = toml::from_str(
+            r#"
+            [features]
+            default = ["std"]
+            std = ["alloc", "serde?/std"]
+            alloc = []
+            derive = ["serde_derive"]
+            unused = []
+            "#,
+        )
+        .unwrap();
+        let features = |spec: &str| {
+            let spec: toml::Value = toml::from_str(spec).unwrap();
+            enabled_features(&manifest, &spec).into_iter().collect::<Vec<_>>()
+        };
+        assert_eq!(features("version = \"=1.0.0\""), ["alloc", "default", "std"]);
+        assert_eq!(
+            features("default-features = false\nfeatures = [\"derive\", \"rand/std\"]"),
+            ["dep:rand", "derive", "serde_derive"]
+        );
+    }
+}
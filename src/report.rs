@@ -0,0 +1,401 @@
+use crate::cicv::{ExerciseCheckList, ExerciseResult, Failure};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Where `cicvverify` writes its results when not told otherwise
+pub const DEFAULT_OUTPUT: &str = ".github/result/check_result";
+
+// The formats `cicvverify` can write its results in
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    // The full `check_result.json`
+    Json,
+    // One test case per exercise, for CI dashboards
+    Junit,
+    // Test Anything Protocol
+    Tap,
+    // A summary for humans, e.g. for `$GITHUB_STEP_SUMMARY`
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "junit" => Ok(Format::Junit),
+            "tap" => Ok(Format::Tap),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "unknown format `{s}`, expected json, junit, tap or markdown"
+            )),
+        }
+    }
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Junit => "xml",
+            Format::Tap => "tap",
+            Format::Markdown => "md",
+        }
+    }
+
+    // The format a file is meant to be written in, going by its extension
+    fn of_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Format::Json),
+            "xml" => Some(Format::Junit),
+            "tap" => Some(Format::Tap),
+            "md" | "markdown" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+}
+
+// Pair up the files to write with their formats. Without outputs, the results
+// go to `DEFAULT_OUTPUT`. Without a format, it follows from the extension.
+pub fn outputs(paths: &[PathBuf], format: Option<Format>) -> Result<Vec<(PathBuf, Format)>, String> {
+    if paths.is_empty() {
+        let format = format.unwrap_or(Format::Json);
+        let path = Path::new(DEFAULT_OUTPUT).with_extension(format.extension());
+        return Ok(vec![(path, format)]);
+    }
+    paths
+        .iter()
+        .map(|path| match format.or_else(|| Format::of_path(path)) {
+            Some(format) => Ok((path.clone(), format)),
+            None => Err(format!(
+                "Can't tell which format to write {} in, pass `--format`",
+                path.display()
+            )),
+        })
+        .collect()
+}
+
+// Write the results to `path`, creating its directory if needed
+pub fn write(results: &ExerciseCheckList, format: Format, path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let content = match format {
+        Format::Json => serde_json::to_string_pretty(results)?,
+        Format::Junit => junit(results),
+        Format::Tap => tap(results),
+        Format::Markdown => markdown(results),
+    };
+    fs::write(path, content)
+}
+
+fn failure_name(failure: Failure) -> &'static str {
+    match failure {
+        Failure::Compile => "compile",
+        Failure::Clippy => "clippy",
+        Failure::Test => "test",
+        Failure::Runtime => "runtime",
+        Failure::Timeout => "timeout",
+    }
+}
+
+// Why the exercise failed in a single line
+pub fn failure_message(result: &ExerciseResult) -> String {
+    let failure = result.failure.map_or("unknown", failure_name);
+    if result.tests.failed.is_empty() {
+        format!("{failure} failure")
+    } else {
+        format!(
+            "{failure} failure, {}, failing: {}",
+            result.tests,
+            result.tests.failed.join(", ")
+        )
+    }
+}
+
+// One test suite per exercise, holding a test case for the exercise itself
+// and one for every test of its test harness
+fn junit(results: &ExerciseCheckList) -> String {
+    let statistics = &results.statistics;
+    let tests = |result: &ExerciseResult| 1 + result.tests.passed.len() + result.tests.failed.len();
+    let failures = |result: &ExerciseResult| usize::from(!result.result) + result.tests.failed.len();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"rustlings\" tests=\"{}\" failures=\"{}\" time=\"{}\">",
+        results.exercises.iter().map(tests).sum::<usize>(),
+        results.exercises.iter().map(failures).sum::<usize>(),
+        statistics.total_time
+    );
+    for result in &results.exercises {
+        let name = escape_xml(&result.name);
+        let time = result.duration_ms as f64 / 1000.0;
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" time=\"{time:.3}\">",
+            tests(result),
+            failures(result)
+        );
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{name}\" classname=\"{}\" time=\"{time:.3}\"",
+            escape_xml(&result.topic)
+        );
+        if result.result {
+            xml.push_str("/>\n");
+        } else {
+            let _ = writeln!(
+                xml,
+                ">\n      <failure type=\"{}\" message=\"{}\">{}</failure>\n    </testcase>",
+                result.failure.map_or("unknown", failure_name),
+                escape_xml(&failure_message(result)),
+                escape_xml(&format!("{}{}", result.stdout, result.stderr))
+            );
+        }
+        for test in &result.tests.passed {
+            let _ = writeln!(xml, "    <testcase name=\"{}\" classname=\"{name}\"/>", escape_xml(test));
+        }
+        for test in &result.tests.failed {
+            let output = test_output(&result.stdout, test).unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{name}\">\n      \
+                 <failure type=\"test\" message=\"test failed\">{}</failure>\n    </testcase>",
+                escape_xml(test),
+                escape_xml(output)
+            );
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// What libtest printed for a failed test, in the `---- <name> stdout ----`
+// section below `failures:`
+fn test_output<'a>(stdout: &'a str, test: &str) -> Option<&'a str> {
+    let header = format!("---- {test} stdout ----\n");
+    let start = stdout.find(&header)? + header.len();
+    let rest = &stdout[start..];
+    let end = ["\n---- ", "\nfailures:"]
+        .iter()
+        .filter_map(|next| rest.find(next))
+        .min()
+        .unwrap_or(rest.len());
+    Some(rest[..end].trim_end())
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML doesn't allow most control characters, not even escaped
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn tap(results: &ExerciseCheckList) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", results.exercises.len());
+    for (i, result) in results.exercises.iter().enumerate() {
+        if result.result {
+            let _ = writeln!(tap, "ok {} - {}", i + 1, result.name);
+        } else {
+            let _ = writeln!(tap, "not ok {} - {} # {}", i + 1, result.name, failure_message(result));
+        }
+    }
+    tap
+}
+
+fn markdown(results: &ExerciseCheckList) -> String {
+    let statistics = &results.statistics;
+    let mut md = String::from("# Rustlings results\n\n");
+    let _ = writeln!(
+        md,
+        "**Score: {:.2} / {}**, {} of {} exercises passed\n",
+        statistics.total_score,
+        statistics.max_score,
+        statistics.total_succeeds,
+        statistics.total_exercations
+    );
+    md.push_str("| Topic | Score |\n| --- | --- |\n");
+    for (topic, score) in &statistics.topics {
+        let _ = writeln!(
+            md,
+            "| {} | {:.2} / {} |",
+            escape_cell(topic),
+            score.score,
+            score.max_score
+        );
+    }
+    let failed = results.exercises.iter().filter(|r| !r.result).collect::<Vec<_>>();
+    if !failed.is_empty() {
+        md.push_str("\n## Failed exercises\n\n| Exercise | Reason | Score |\n| --- | --- | --- |\n");
+        for result in failed {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {:.2} / {} |",
+                escape_cell(&result.name),
+                escape_cell(&failure_message(result)),
+                result.score,
+                result.max_score
+            );
+        }
+    }
+    let hinted = results
+        .exercises
+        .iter()
+        .filter_map(|r| r.hints_used.filter(|&used| used > 0).map(|used| (&r.name, used)))
+        .collect::<Vec<_>>();
+    if !hinted.is_empty() {
+        md.push_str("\n## Hints used\n\n| Exercise | Hints |\n| --- | --- |\n");
+        for (name, used) in hinted {
+            let _ = writeln!(md, "| {} | {used} |", escape_cell(name));
+        }
+    }
+    md
+}
+
+// A `|` would end the table cell, and a line break the whole row
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{Mode, TestResults};
+
+    fn results() -> ExerciseCheckList {
+        let exercises = vec![
+            ExerciseResult {
+                name: "intro1".into(),
+                topic: "intro".into(),
+                result: true,
+                mode: Mode::Compile,
+                failure: None,
+                duration_ms: 1500,
+                stdout: String::new(),
+                stderr: String::new(),
+                tests: TestResults::default(),
+                score: 1.0,
+                max_score: 1,
+                hints_used: None,
+            },
+            ExerciseResult {
+                name: "tests<1>".into(),
+                topic: "tests".into(),
+                result: false,
+                mode: Mode::Test,
+                failure: Some(Failure::Test),
+                duration_ms: 250,
+                stdout: "running 2 tests\n\
+                         panicked & failed\n\
+                         \n\
+                         failures:\n\
+                         \n\
+                         ---- second stdout ----\n\
+                         assertion failed: 1 < 0\n\
+                         \n\
+                         failures:\n\
+                         \x20   second\n"
+                    .into(),
+                stderr: String::new(),
+                tests: TestResults {
+                    passed: vec!["first".into()],
+                    failed: vec!["second".into()],
+                },
+                score: 1.0,
+                max_score: 2,
+                hints_used: None,
+            },
+        ];
+        ExerciseCheckList::new(exercises, 2)
+    }
+
+    #[test]
+    fn test_outputs() {
+        assert_eq!(
+            outputs(&[], None).unwrap(),
+            [(PathBuf::from(".github/result/check_result.json"), Format::Json)]
+        );
+        assert_eq!(
+            outputs(&[], Some(Format::Junit)).unwrap(),
+            [(PathBuf::from(".github/result/check_result.xml"), Format::Junit)]
+        );
+        assert_eq!(
+            outputs(&["a.md".into(), "b.out".into()], Some(Format::Tap)).unwrap(),
+            [("a.md".into(), Format::Tap), ("b.out".into(), Format::Tap)]
+        );
+        assert_eq!(
+            outputs(&["a.md".into(), "b.xml".into()], None).unwrap(),
+            [("a.md".into(), Format::Markdown), ("b.xml".into(), Format::Junit)]
+        );
+        assert!(outputs(&["b.out".into()], None).is_err());
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&results());
+        assert!(xml.contains("<testsuites name=\"rustlings\" tests=\"4\" failures=\"2\" time=\"2\">"));
+        assert!(xml.contains(
+            "  <testsuite name=\"intro1\" tests=\"1\" failures=\"0\" time=\"1.500\">\n    \
+             <testcase name=\"intro1\" classname=\"intro\" time=\"1.500\"/>\n  </testsuite>\n"
+        ));
+        assert!(xml.contains(
+            "  <testsuite name=\"tests&lt;1&gt;\" tests=\"3\" failures=\"2\" time=\"0.250\">\n    \
+             <testcase name=\"tests&lt;1&gt;\" classname=\"tests\" time=\"0.250\">\n      \
+             <failure type=\"test\" message=\"test failure, 1/2 tests passing, failing: second\">\
+             running 2 tests\npanicked &amp; failed\n"
+        ));
+        assert!(xml.contains("    <testcase name=\"first\" classname=\"tests&lt;1&gt;\"/>\n"));
+        assert!(xml.contains(
+            "    <testcase name=\"second\" classname=\"tests&lt;1&gt;\">\n      \
+             <failure type=\"test\" message=\"test failed\">assertion failed: 1 &lt; 0</failure>\n    \
+             </testcase>\n  </testsuite>\n</testsuites>\n"
+        ));
+    }
+
+    #[test]
+    fn test_tap() {
+        assert_eq!(
+            tap(&results()),
+            "TAP version 13\n1..2\nok 1 - intro1\n\
+             not ok 2 - tests<1> # test failure, 1/2 tests passing, failing: second\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = markdown(&results());
+        assert!(md.contains("**Score: 2.00 / 3**, 1 of 2 exercises passed"));
+        assert!(md.contains("| intro | 1.00 / 1 |\n| tests | 1.00 / 2 |\n"));
+        assert!(md.contains("| tests<1> | test failure, 1/2 tests passing, failing: second | 1.00 / 2 |"));
+        assert!(!md.contains("Hints used"));
+
+        let mut escaped = results();
+        escaped.exercises[1].name = "a|b".into();
+        escaped.exercises[1].score = 2.0 / 3.0;
+        escaped.exercises[1].tests.failed = vec!["x|y".into()];
+        let md = markdown(&escaped);
+        assert!(md.contains("| a\\|b | test failure, 1/2 tests passing, failing: x\\|y | 0.67 / 2 |"));
+
+        let mut results = results();
+        results.exercises[0].hints_used = Some(0);
+        results.exercises[1].hints_used = Some(2);
+        assert!(markdown(&results)
+            .ends_with("## Hints used\n\n| Exercise | Hints |\n| --- | --- |\n| tests<1> | 2 |\n"));
+    }
+}
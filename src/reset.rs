@@ -0,0 +1,130 @@
+use crate::exercise::{normalize, Exercise};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+include!(concat!(env!("OUT_DIR"), "/pristine_exercises.rs"));
+
+// Where the learner's versions of the last reset exercises are kept
+pub const BACKUP_DIR: &str = ".rustlings-backup";
+
+// The original content of the exercise at `path`, as it was first committed
+// or, outside of a git checkout, as it was when rustlings was installed. Always
+// `None` when built in a shallow clone.
+pub fn pristine(path: &Path) -> Option<&'static str> {
+    let key = normalize(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    PRISTINE_EXERCISES
+        .iter()
+        .find(|(path, _)| *path == key)
+        .map(|(_, content)| *content)
+}
+
+// The exercises matching `target`, which is either the name of an exercise,
+// the path of an exercise or the name or path of a topic directory
+pub fn select<'a>(target: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+    let target = target.trim_end_matches('/');
+    if let Some(exercise) = exercises
+        .iter()
+        .find(|e| e.name == target || e.path == Path::new(target))
+    {
+        return vec![exercise];
+    }
+    exercises
+        .iter()
+        .filter(|e| {
+            e.path.parent().is_some_and(|topic| {
+                topic == Path::new(target) || topic.file_name().is_some_and(|name| name == target)
+            })
+        })
+        .collect()
+}
+
+// Restore the pristine content of the given exercises. The versions being
+// replaced are moved to the backup directory, replacing the previous backup.
+pub fn reset(exercises: &[&Exercise]) -> Result<(), ()> {
+    let backup_dir = Path::new(BACKUP_DIR);
+    let mut changed = vec![];
+    for exercise in exercises {
+        let Some(content) = pristine(&exercise.path) else {
+            warn!("There is no original version of {} to reset to", exercise);
+            return Err(());
+        };
+        if fs::read_to_string(&exercise.path).ok().as_deref() != Some(content) {
+            changed.push((exercise, content));
+        }
+    }
+    if changed.is_empty() {
+        println!("Nothing to reset, the exercises are unchanged.");
+        return Ok(());
+    }
+
+    let _ignored = fs::remove_dir_all(backup_dir);
+    for (exercise, content) in changed {
+        if let Err(e) = backup(&exercise.path, backup_dir).and_then(|_| fs::write(&exercise.path, content))
+        {
+            warn!("Failed to reset {}", format!("{exercise}: {e}"));
+            return Err(());
+        }
+        success!("Reset {}", exercise);
+    }
+    println!("Run `rustlings reset --undo` to get your version back.");
+    Ok(())
+}
+
+// Put the learner's versions of the last reset exercises back in place
+pub fn undo() -> Result<(), ()> {
+    let backup_dir = Path::new(BACKUP_DIR);
+    let mut files = vec![];
+    collect_files(backup_dir, &mut files).map_err(|_| {
+        println!("There is no reset to undo.");
+    })?;
+    for file in files {
+        let original = file.strip_prefix(backup_dir).unwrap();
+        if let Err(e) = fs::copy(&file, original) {
+            warn!("Failed to restore {}", format!("{}: {e}", original.display()));
+            return Err(());
+        }
+        success!("Restored {}", original.display());
+    }
+    let _ignored = fs::remove_dir_all(backup_dir);
+    Ok(())
+}
+
+fn backup(path: &Path, backup_dir: &Path) -> io::Result<()> {
+    let target = backup_dir.join(path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(path, target).map(|_| ())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pristine_normalizes_paths() {
+        // A shallow clone has no originals to look up
+        if let Some(&(path, content)) = PRISTINE_EXERCISES.first() {
+            assert_eq!(pristine(Path::new(path)), Some(content));
+            assert_eq!(pristine(&Path::new(".").join(path)), Some(content));
+        }
+        assert_eq!(pristine(Path::new("exercises/missing.rs")), None);
+    }
+}
@@ -1,7 +1,5 @@
-use std::process::Command;
-
 use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
+use crate::verify::{test, warn_timed_out};
 use indicatif::ProgressBar;
 
 // Invoke the rust compiler on the path of the given exercise,
@@ -14,23 +12,11 @@ pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
         Mode::Compile => compile_and_run(exercise)?,
         Mode::Clippy => compile_and_run(exercise)?,
         Mode::BuildScript => test(exercise, verbose)?,
+        Mode::Cargo => test(exercise, verbose)?,
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
-pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
-
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
-    }
-}
-
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
@@ -67,7 +53,11 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("Ran {} with errors", exercise);
+            }
             Err(())
         }
     }
@@ -0,0 +1,442 @@
+use crate::exercise::{normalize, Exercise};
+use crate::progress::ProgressState;
+use glob::Pattern;
+use regex::Regex;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::str::FromStr;
+
+// Exercises picked on the command line by comma separated selectors. A
+// selector is one of
+// - `re:<regex>`, matching the names, paths and topics of exercises. It has
+//   to be the only selector, as regular expressions may contain commas.
+// - `<first>..<last>`, the exercises from `first` to `last` in `info.toml` order.
+//   Exercises of a track are named once, like `<track>/<first>..<last>`.
+// - a glob like `algorithm*`, matching names, paths and topics
+// - the name or path of an exercise, or the name or directory of a topic
+// - any other text, matching names and paths containing it, ignoring case
+pub struct Selection(Vec<Selector>);
+
+enum Selector {
+    Regex(Regex),
+    Range(String, String),
+    Glob(Pattern),
+    Text(String),
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(regex) = s.strip_prefix("re:") {
+            return match Regex::new(regex) {
+                Ok(regex) => Ok(Selection(vec![Selector::Regex(regex)])),
+                Err(e) => Err(format!("invalid regular expression `{regex}`: {e}")),
+            };
+        }
+        s.split(',')
+            .map(str::trim)
+            .filter(|selector| !selector.is_empty())
+            .map(|selector| {
+                // Only after the last `/`, so that paths like `../a.rs` aren't ranges
+                let (track, range) = match selector.rsplit_once('/') {
+                    Some((track, range)) => (format!("{track}/"), range),
+                    None => (String::new(), selector),
+                };
+                if let Some((first, last)) = range.split_once("..") {
+                    if !first.is_empty() && !last.is_empty() {
+                        return Ok(Selector::Range(track.clone() + first, track + last));
+                    }
+                }
+                if selector.contains(['*', '?', '[']) {
+                    return Pattern::new(selector)
+                        .map(Selector::Glob)
+                        .map_err(|e| format!("invalid pattern `{selector}`: {e}"));
+                }
+                Ok(Selector::Text(selector.trim_end_matches('/').into()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Selection)
+    }
+}
+
+impl Selection {
+    // The selected exercises, in the order of `exercises`
+    pub fn select<'a>(&self, exercises: &'a [Exercise]) -> Result<Vec<&'a Exercise>, String> {
+        let mut selected = vec![false; exercises.len()];
+        for selector in &self.0 {
+            for i in selector.indices(exercises)? {
+                selected[i] = true;
+            }
+        }
+        Ok(exercises
+            .iter()
+            .zip(selected)
+            .filter_map(|(exercise, selected)| selected.then_some(exercise))
+            .collect())
+    }
+}
+
+impl Selector {
+    fn indices(&self, exercises: &[Exercise]) -> Result<Vec<usize>, String> {
+        let matching = |matches: &dyn Fn(&Exercise) -> bool| {
+            (0..exercises.len())
+                .filter(|&i| matches(&exercises[i]))
+                .collect::<Vec<_>>()
+        };
+        Ok(match self {
+            Selector::Regex(regex) => matching(&|e| {
+                regex.is_match(&e.name)
+                    || regex.is_match(&e.path.to_string_lossy())
+                    || regex.is_match(&e.topic())
+            }),
+            Selector::Range(first, last) => {
+                let position = |name: &str| {
+                    exercises
+                        .iter()
+                        .position(|e| e.name == name)
+                        .ok_or_else(|| format!("No exercise found for '{name}'!"))
+                };
+                let (first, last) = (position(first)?, position(last)?);
+                (first.min(last)..=first.max(last)).collect()
+            }
+            Selector::Glob(pattern) => matching(&|e| {
+                pattern.matches(&e.name) || pattern.matches_path(&e.path) || pattern.matches(&e.topic())
+            }),
+            Selector::Text(text) => {
+                let path = Path::new(text);
+                let exact = matching(&|e| e.name == *text || e.path == path);
+                if !exact.is_empty() {
+                    return Ok(exact);
+                }
+                let topic = matching(&|e| e.topic() == *text || e.path.parent() == Some(path));
+                if !topic.is_empty() {
+                    return Ok(topic);
+                }
+                let text = text.to_lowercase();
+                matching(&|e| {
+                    e.name.to_lowercase().contains(&text)
+                        || e.path.to_string_lossy().to_lowercase().contains(&text)
+                })
+            }
+        })
+    }
+}
+
+// The exercise meant by `name`, which is one of
+// - `next`, the first exercise that isn't done
+// - `current`, the exercise watch mode is at: the first one that is neither
+//   done nor skipped
+// - `prev`, the exercise before the current one
+// - `random`, any exercise that isn't done
+// - the name or path of an exercise
+// - the start of the name of a single exercise
+// When there is no such exercise, the error suggests names close to `name`.
+pub fn find<'a>(
+    name: &str,
+    exercises: &'a [Exercise],
+    progress_state: &ProgressState,
+) -> Result<&'a Exercise, String> {
+    match find_exact(name, exercises, progress_state)? {
+        Some(exercise) => Ok(exercise),
+        None => find_by_prefix(name, exercises),
+    }
+}
+
+// The exercises `rustlings run` runs for `name`: the exercise `find` finds,
+// all exercises of a topic, or the exercises picked by globs, ranges or
+// regular expressions as described for `Selection`
+pub fn find_all<'a>(
+    name: &str,
+    exercises: &'a [Exercise],
+    progress_state: &ProgressState,
+) -> Result<Vec<&'a Exercise>, String> {
+    if let Some(exercise) = find_exact(name, exercises, progress_state)? {
+        return Ok(vec![exercise]);
+    }
+    let selection = name.parse::<Selection>()?;
+    let text = match &selection.0[..] {
+        [Selector::Text(text)] => text,
+        _ => {
+            let selected = selection.select(exercises)?;
+            if selected.is_empty() {
+                return Err(format!("No exercises match `{name}`."));
+            }
+            return Ok(selected);
+        }
+    };
+    let path = Path::new(text);
+    let topic = exercises
+        .iter()
+        .filter(|e| e.topic() == *text || e.path.parent() == Some(path))
+        .collect::<Vec<_>>();
+    if !topic.is_empty() {
+        return Ok(topic);
+    }
+    find_by_prefix(name, exercises).map(|exercise| vec![exercise])
+}
+
+// The exercise named by a keyword, or by its exact name or path
+fn find_exact<'a>(
+    name: &str,
+    exercises: &'a [Exercise],
+    progress_state: &ProgressState,
+) -> Result<Option<&'a Exercise>, String> {
+    let all_done = || {
+        "🎉 Congratulations! You have done all the exercises!\n\
+         🔚 There are no more exercises to do next!"
+            .to_string()
+    };
+    let unsolved = || exercises.iter().filter(|e| !progress_state.is_done(e));
+    let current = || {
+        unsolved()
+            .find(|e| !progress_state.is_skipped(e))
+            .or_else(|| unsolved().next())
+    };
+    let exercise = match name {
+        "next" => unsolved().next().ok_or_else(all_done)?,
+        "current" => current().ok_or_else(all_done)?,
+        "prev" => {
+            let position = current().map_or(exercises.len(), |current| {
+                exercises.iter().position(|e| e.name == current.name).unwrap_or(0)
+            });
+            match position.checked_sub(1) {
+                Some(prev) => &exercises[prev],
+                None => return Err("There is no exercise before the current one.".into()),
+            }
+        }
+        "random" => {
+            let unsolved = unsolved().collect::<Vec<_>>();
+            if unsolved.is_empty() {
+                return Err(all_done());
+            }
+            // The keys of the first `RandomState` of a thread come from the
+            // randomness of the operating system, so hashing nothing with them
+            // picks a random number without a crate for it. The pick only has
+            // to be unpredictable to the learner, not secure.
+            let random = RandomState::new().build_hasher().finish() as usize;
+            unsolved[random % unsolved.len()]
+        }
+        _ => {
+            let path = normalize(Path::new(name));
+            return Ok(exercises
+                .iter()
+                .find(|e| e.name == name || normalize(&e.path) == path)
+                .or_else(|| {
+                    // An absolute path, or one relative to somewhere else
+                    let matches = exercises
+                        .iter()
+                        .filter(|e| path.ends_with(normalize(&e.path)))
+                        .collect::<Vec<_>>();
+                    (matches.len() == 1).then(|| matches[0])
+                }));
+        }
+    };
+    Ok(Some(exercise))
+}
+
+// The only exercise whose name starts with `name`
+fn find_by_prefix<'a>(name: &str, exercises: &'a [Exercise]) -> Result<&'a Exercise, String> {
+    let candidates = exercises
+        .iter()
+        .filter(|e| e.name.starts_with(name))
+        .collect::<Vec<_>>();
+    match candidates[..] {
+        [exercise] => Ok(exercise),
+        [] => {
+            let mut message = format!("No exercise found for '{name}'!");
+            let suggestions = suggestions(name, exercises);
+            if !suggestions.is_empty() {
+                message.push_str(&format!(" Did you mean {}?", or_list(&suggestions)));
+            }
+            Err(message)
+        }
+        _ => {
+            let names = candidates.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+            Err(format!("'{name}' could be {}. Which one do you mean?", or_list(&names)))
+        }
+    }
+}
+
+// The names of up to three exercises closest to `name`, closest first,
+// as long as they are close enough to be a typo of it
+fn suggestions<'a>(name: &str, exercises: &'a [Exercise]) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+    let mut close = exercises
+        .iter()
+        .map(|e| (edit_distance(&name, &e.name.to_lowercase()), e.name.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    close.sort_by_key(|(distance, _)| *distance);
+    close.into_iter().take(3).map(|(_, name)| name).collect()
+}
+
+// The Levenshtein distance: how many characters have to be inserted, removed
+// or replaced to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// `a`, `b` or `c`, with more than six names shortened
+fn or_list(names: &[&str]) -> String {
+    let mut quoted = names.iter().take(6).map(|name| format!("`{name}`")).collect::<Vec<_>>();
+    if names.len() > 6 {
+        quoted.push(format!("{} more", names.len() - 6));
+    }
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {last}", rest.join(", ")),
+        _ => quoted.concat(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+
+    fn exercises() -> ExerciseList {
+        toml::from_str(
+            r#"
+            [[exercises]]
+            name = "iterators1"
+            path = "exercises/iterators/iterators1.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "iterators2"
+            path = "exercises/iterators/iterators2.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "iterators3"
+            path = "exercises/iterators/iterators3.rs"
+            mode = "test"
+            hint = ""
+
+            [[exercises]]
+            name = "algorithm1"
+            path = "exercises/algorithm/algorithm1.rs"
+            mode = "test"
+            hint = ""
+
+            [[exercises]]
+            name = "algorithm10"
+            path = "exercises/algorithm/algorithm10.rs"
+            mode = "test"
+            hint = ""
+            topic = "graphs"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn select(selection: &str) -> Result<Vec<String>, String> {
+        let list = exercises();
+        let selection = selection.parse::<Selection>()?;
+        let selected = selection.select(&list.exercises)?;
+        Ok(selected.into_iter().map(|e| e.name.clone()).collect())
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select("algorithm1").unwrap(), ["algorithm1"]);
+        assert_eq!(select("exercises/algorithm/").unwrap(), ["algorithm1", "algorithm10"]);
+        assert_eq!(select("graphs").unwrap(), ["algorithm10"]);
+        assert_eq!(select("ITER").unwrap(), ["iterators1", "iterators2", "iterators3"]);
+        assert_eq!(select("algorithm*").unwrap(), ["algorithm1", "algorithm10"]);
+        assert_eq!(select("re:^iterators[13]$").unwrap(), ["iterators1", "iterators3"]);
+        assert_eq!(select("iterators3..iterators2").unwrap(), ["iterators2", "iterators3"]);
+        assert_eq!(select("algorithm10, iterators1").unwrap(), ["iterators1", "algorithm10"]);
+        assert!(select("nothing").unwrap().is_empty());
+        assert!(select("iterators1..iterators9").is_err());
+        assert!(select("re:(").is_err());
+    }
+
+    #[test]
+    fn test_select_range_of_track() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "extra/one"
+            path = "tracks/extra/one.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "extra/two"
+            path = "tracks/extra/two.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            name = "extra/three"
+            path = "tracks/extra/three.rs"
+            mode = "compile"
+            hint = ""
+            "#,
+        )
+        .unwrap();
+        let select = |selection: &str| {
+            let selection = selection.parse::<Selection>().unwrap();
+            let selected = selection.select(&list.exercises).unwrap();
+            selected.into_iter().map(|e| e.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(select("extra/one..two"), ["extra/one", "extra/two"]);
+        assert_eq!(select("extra/three..two"), ["extra/two", "extra/three"]);
+        assert_eq!(select("../extra/one.rs"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_find() {
+        let list = exercises();
+        let state = ProgressState::load_from(Path::new("tests/fixture/no_state.json"), false);
+        let find = |name: &str| find(name, &list.exercises, &state).map(|e| e.name.as_str());
+        assert_eq!(find("next"), Ok("iterators1"));
+        assert_eq!(find("current"), Ok("iterators1"));
+        assert!(find("prev").is_err());
+        assert!(find("random").is_ok());
+        assert_eq!(find("./exercises/iterators/iterators3.rs"), Ok("iterators3"));
+        assert_eq!(find("/home/me/rustlings/exercises/iterators/iterators3.rs"), Ok("iterators3"));
+        // A full name wins over the longer names starting with it
+        assert_eq!(find("algorithm1"), Ok("algorithm1"));
+        assert_eq!(find("iterators"), Err(
+            "'iterators' could be `iterators1`, `iterators2` or `iterators3`. Which one do you mean?"
+                .into()
+        ));
+        assert_eq!(find("algorithm"), Err(
+            "'algorithm' could be `algorithm1` or `algorithm10`. Which one do you mean?".into()
+        ));
+        assert_eq!(find("algoritm10"), Err(
+            "No exercise found for 'algoritm10'! Did you mean `algorithm10` or `algorithm1`?".into()
+        ));
+        assert_eq!(find("closures"), Err("No exercise found for 'closures'!".into()));
+
+        let run = |name: &str| find_all(name, &list.exercises, &state).map(|found| found.len());
+        assert_eq!(run("iterators"), Ok(3));
+        assert_eq!(run("iterators1..iterators2"), Ok(2));
+        assert_eq!(run("algorithm1"), Ok(1));
+        assert!(run("iter").unwrap_err().starts_with("'iter' could be `iterators1`"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("iterators", "iterators"), 0);
+    }
+}
@@ -0,0 +1,368 @@
+use crate::exercise::{Exercise, ExerciseOutput};
+use crate::progress::ProgressState;
+use crate::watcher::Events;
+use crate::{edited_exercises, edited_file, WatchStatus};
+use ansi_to_tui::IntoText;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::Duration;
+
+const KEYS: &str = "h hint  n next  s skip  r rerun  l list  ↑↓ scroll  q quit";
+
+// Watch mode as a full screen terminal UI, used when stdout is a terminal.
+// Returns once all exercises are done or the user quits.
+pub fn watch(
+    exercises: &[Exercise],
+    progress_state: &mut ProgressState,
+    events: &Events,
+    success_hints: bool,
+) -> io::Result<WatchStatus> {
+    // Like in plain watch mode, nothing selected means nothing left to do
+    if exercises.is_empty() {
+        return Ok(WatchStatus::Finished);
+    }
+    let mut terminal = ratatui::try_init()?;
+    let mut app = App {
+        exercises,
+        progress_state,
+        current: 0,
+        passed: false,
+        output: Text::default(),
+        scroll: 0,
+        show_hint: false,
+        show_list: true,
+        success_hints,
+        status: String::new(),
+    };
+    let result = app.run(&mut terminal, events);
+    ratatui::restore();
+    result
+}
+
+struct App<'a> {
+    exercises: &'a [Exercise],
+    progress_state: &'a mut ProgressState,
+    // The exercise shown in the output panel
+    current: usize,
+    // Whether the last check of the current exercise passed
+    passed: bool,
+    // What compiling and running the current exercise printed
+    output: Text<'static>,
+    // How many lines of the output are scrolled out of view
+    scroll: u16,
+    show_hint: bool,
+    show_list: bool,
+    success_hints: bool,
+    // What happened last, shown above the keys
+    status: String,
+}
+
+impl App<'_> {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        events: &Events,
+    ) -> io::Result<WatchStatus> {
+        if self.advance(terminal)? {
+            return Ok(WatchStatus::Finished);
+        }
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(WatchStatus::Unfinished),
+                        KeyCode::Char('h') => self.next_hint(),
+                        KeyCode::Char('l') => self.show_list = !self.show_list,
+                        KeyCode::Char('r') => self.check_current(terminal)?,
+                        KeyCode::Char('s') => {
+                            self.progress_state.skip(&self.exercises[self.current]);
+                            if self.advance(terminal)? {
+                                return Ok(WatchStatus::Finished);
+                            }
+                        }
+                        KeyCode::Char('n') => match self.next_pending() {
+                            Some(next) => {
+                                self.current = next;
+                                self.check_current(terminal)?;
+                            }
+                            None => self.status = "There are no other exercises left to do".into(),
+                        },
+                        KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+                        KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+                        KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+                        KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+                        _ => {}
+                    }
+                }
+            }
+
+            // Saving several files at once is handled like saving the last of them
+            let exercises = self.exercises;
+            let mut edited_files = vec![];
+            for batch in events.try_iter() {
+                match batch {
+                    Ok(paths) => edited_files.extend(paths),
+                    Err(e) => self.status = format!("watch error: {e}"),
+                }
+            }
+            let Some(path) = edited_files.into_iter().rev().find_map(|p| edited_file(p, exercises)) else {
+                continue;
+            };
+            // Check the exercises depending on a shared file up to the first
+            // one that doesn't pass yet
+            let edited = edited_exercises(&path, exercises, self.progress_state);
+            if edited.is_empty() {
+                self.check_current(terminal)?;
+            }
+            for exercise in edited {
+                self.current = exercises.iter().position(|e| e.name == exercise.name).unwrap_or(0);
+                self.check_current(terminal)?;
+                if !self.progress_state.is_done(exercise) {
+                    break;
+                }
+            }
+            if self.progress_state.is_done(&self.exercises[self.current]) && self.advance(terminal)? {
+                return Ok(WatchStatus::Finished);
+            }
+        }
+    }
+
+    // Check the exercises that aren't done or skipped in order, stopping at
+    // the first one that still doesn't pass. Returns whether all exercises are done.
+    fn advance(&mut self, terminal: &mut DefaultTerminal) -> io::Result<bool> {
+        let exercises = self.exercises;
+        let mut passed = vec![];
+        for (i, exercise) in exercises.iter().enumerate() {
+            if self.progress_state.is_done(exercise) || self.progress_state.is_skipped(exercise) {
+                continue;
+            }
+            self.current = i;
+            self.check_current(terminal)?;
+            if !self.progress_state.is_done(exercise) {
+                if !passed.is_empty() {
+                    self.status = format!("{} passed! {}", passed.join(", "), self.status);
+                }
+                return Ok(false);
+            }
+            passed.push(exercise.name.as_str());
+        }
+        let all_done = exercises.iter().all(|e| self.progress_state.is_done(e));
+        if !all_done {
+            self.status = "Only the exercises you skipped are left, press n to get back to them".into();
+        }
+        Ok(all_done)
+    }
+
+    // Check the current exercise like `rustlings verify`, recording it when it passes
+    fn check_current(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let exercises = self.exercises;
+        let Some(exercise) = exercises.get(self.current) else {
+            return Ok(());
+        };
+        self.status = format!("Checking {exercise}...");
+        terminal.draw(|frame| self.draw(frame))?;
+
+        let (passed, output) = match exercise.compile() {
+            Ok(compiled) => match compiled.check() {
+                Ok(output) => (true, output),
+                Err(output) => (false, output),
+            },
+            Err(output) => (false, output),
+        };
+        self.passed = passed;
+        self.scroll = 0;
+        let tests = output.test_results();
+        let output = describe(exercise, &output);
+        self.output = output.into_text().unwrap_or_else(|_| Text::raw(output));
+
+        self.status = if !passed && !tests.failed.is_empty() {
+            format!("{exercise} doesn't pass yet, {tests}")
+        } else if !passed {
+            format!("{exercise} doesn't pass yet")
+        } else {
+            self.progress_state.record_pass(exercise);
+            if self.success_hints {
+                self.show_hint = true;
+            }
+            if self.progress_state.is_done(exercise) {
+                format!("{exercise} passes!")
+            } else {
+                format!("{exercise} passes! Remove its `I AM NOT DONE` comment to move on")
+            }
+        };
+        Ok(())
+    }
+
+    // Show the hints seen so far, then one more each time until all of them
+    // are shown, then hide them again
+    fn next_hint(&mut self) {
+        let exercise = &self.exercises[self.current];
+        let used = self.progress_state.hints_used(exercise);
+        if self.show_hint && used >= exercise.hint_levels() {
+            self.show_hint = false;
+            return;
+        }
+        if self.show_hint || used == 0 {
+            let used = self.progress_state.use_hint(exercise);
+            let left = exercise.hint_levels() - used;
+            if left > 0 {
+                self.status = format!("{left} more left, press h again for the next hint");
+            }
+        }
+        self.show_hint = true;
+    }
+
+    // The next exercise after the current one that isn't done, wrapping around
+    fn next_pending(&self) -> Option<usize> {
+        let len = self.exercises.len();
+        (1..len)
+            .map(|offset| (self.current + offset) % len)
+            .find(|&i| !self.progress_state.is_done(&self.exercises[i]))
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, progress, status] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, body] = if self.show_list {
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(main)
+        } else {
+            [Rect::default(), main]
+        };
+        let [output, hint] = if self.show_hint {
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body)
+        } else {
+            [body, Rect::default()]
+        };
+
+        if self.show_list {
+            self.draw_list(frame, list);
+        }
+        self.draw_output(frame, output);
+        if self.show_hint {
+            let exercise = &self.exercises[self.current];
+            let text = if self.passed && self.success_hints {
+                exercise.full_hint()
+            } else {
+                exercise.hint(self.progress_state.hints_used(exercise))
+            };
+            let paragraph = Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Hint "));
+            frame.render_widget(paragraph, hint);
+        }
+        self.draw_progress(frame, progress);
+
+        let status_line = Line::from(vec![
+            Span::raw(format!("{}  ", self.status)),
+            Span::styled(KEYS, Style::new().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+
+    fn draw_list(&self, frame: &mut Frame, area: Rect) {
+        let items = self.exercises.iter().enumerate().map(|(i, exercise)| {
+            if self.progress_state.is_done(exercise) {
+                ListItem::new(format!("✓ {}", exercise.name)).green()
+            } else if i == self.current {
+                ListItem::new(format!("▶ {}", exercise.name))
+            } else if self.progress_state.is_skipped(exercise) {
+                ListItem::new(format!("- {}", exercise.name)).dark_gray()
+            } else {
+                ListItem::new(format!("· {}", exercise.name))
+            }
+        });
+        let list = List::new(items)
+            .block(Block::bordered().title(" Exercises "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.current));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_output(&self, frame: &mut Frame, area: Rect) {
+        let exercise = &self.exercises[self.current];
+        let verdict = if self.passed {
+            Span::styled(" passing ", Style::new().fg(Color::Green))
+        } else {
+            Span::styled(" failing ", Style::new().fg(Color::Red))
+        };
+        let mut block = Block::bordered().title(Line::from(vec![
+            Span::raw(format!(" {exercise} ")),
+            verdict,
+        ]));
+        if let Some(summary) = exercise.metadata_summary() {
+            block = block.title_bottom(format!(" {summary} "));
+        }
+        let paragraph = Paragraph::new(self.output.clone())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .block(block);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_progress(&self, frame: &mut Frame, area: Rect) {
+        let total = self.exercises.len();
+        let done = self
+            .exercises
+            .iter()
+            .filter(|e| self.progress_state.is_done(e))
+            .count();
+        let gauge = Gauge::default()
+            .ratio(if total == 0 { 1.0 } else { done as f64 / total as f64 })
+            .label(format!("{done}/{total} exercises done"))
+            .gauge_style(Style::new().fg(Color::Green).bg(Color::DarkGray));
+        frame.render_widget(gauge, area);
+    }
+}
+
+// What compiling and running the exercise printed, noting when it was killed
+fn describe(exercise: &Exercise, output: &ExerciseOutput) -> String {
+    let mut text = String::new();
+    if output.timed_out {
+        text.push_str(&format!(
+            "{exercise} timed out after {} seconds! Is there an infinite loop?\n\n",
+            exercise.limits().timeout.as_secs()
+        ));
+    }
+    for part in [&output.stdout, &output.stderr] {
+        if !part.trim().is_empty() {
+            text.push_str(part);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_describe_timeout() {
+        let mut exercise = Exercise::new("example", "example.rs", Mode::Compile);
+        exercise.timeout = Some(3);
+        let output = ExerciseOutput {
+            stdout: "looping\n".into(),
+            stderr: " \n".into(),
+            timed_out: true,
+        };
+        assert_eq!(
+            describe(&exercise, &output),
+            "example.rs timed out after 3 seconds! Is there an infinite loop?\n\nlooping\n\n"
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::cicv::{grade, ExerciseResult};
+use crate::exercise::{BuildDir, CompiledExercise, Exercise, ExerciseOutput, Mode, State};
+use crate::progress::ProgressState;
+use crate::report::failure_message;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
@@ -8,9 +11,11 @@ use std::env;
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// Every exercise that passes is recorded in the progress state.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
+    progress_state: &mut ProgressState,
     verbose: bool,
     success_hints: bool,
 ) -> Result<(), &'a Exercise> {
@@ -25,12 +30,13 @@ pub fn verify<'a>(
     bar.set_message(format!("({:.1} %)", percentage));
 
     for exercise in exercises {
+        let run_mode = RunMode::Interactive(progress_state);
         let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
+            Mode::Test => compile_and_test(exercise, run_mode, verbose, success_hints),
+            Mode::Compile => compile_and_run_interactively(exercise, run_mode, success_hints),
+            Mode::Clippy => compile_only(exercise, run_mode, success_hints),
+            Mode::BuildScript => compile_and_test(exercise, run_mode, verbose, success_hints),
+            Mode::Cargo => compile_and_test(exercise, run_mode, verbose, success_hints),
         };
         if !compile_result.unwrap_or(false) {
             return Err(exercise);
@@ -42,8 +48,63 @@ pub fn verify<'a>(
     Ok(())
 }
 
-enum RunMode {
-    Interactive,
+// Check every exercise instead of stopping at the first one that fails,
+// telling how each of them went. Every exercise that passes is recorded.
+pub fn grade_all(exercises: &[Exercise], progress_state: &mut ProgressState) -> Vec<ExerciseResult> {
+    let mut results = vec![];
+    for exercise in exercises {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Checking {exercise}..."));
+        progress_bar.enable_steady_tick(100);
+        let result = grade(exercise, &BuildDir::Shared);
+        progress_bar.finish_and_clear();
+
+        if result.result {
+            progress_state.record_pass(exercise);
+            success!("{} passed", exercise);
+        } else {
+            warn!("{}", format!("{exercise} failed: {}", failure_message(&result)));
+        }
+        results.push(result);
+    }
+    results
+}
+
+// Show how many exercises of each topic pass and which ones don't,
+// with the topics in the order of their first exercise
+pub fn print_summary(results: &[ExerciseResult]) {
+    let mut topics: Vec<(&str, Vec<&ExerciseResult>)> = vec![];
+    for result in results {
+        match topics.iter_mut().find(|(topic, _)| *topic == result.topic) {
+            Some((_, topic_results)) => topic_results.push(result),
+            None => topics.push((&result.topic, vec![result])),
+        }
+    }
+    let width = topics.iter().map(|(topic, _)| topic.len()).max().unwrap_or(0).max(5);
+
+    println!();
+    println!("{:<width$}  {:>7}  Failing", style("Topic").bold(), style("Passed").bold());
+    for (topic, topic_results) in &topics {
+        let failing = topic_results
+            .iter()
+            .filter(|r| !r.result)
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>();
+        let passed = format!("{}/{}", topic_results.len() - failing.len(), topic_results.len());
+        let topic = if topic.is_empty() { "-" } else { topic };
+        if failing.is_empty() {
+            println!("{:<width$}  {:>7}", topic, style(passed).green());
+        } else {
+            println!("{:<width$}  {:>7}  {}", topic, style(passed).red(), failing.join(", "));
+        }
+    }
+    let passed = results.iter().filter(|r| r.result).count();
+    println!("{:<width$}  {:>7}", style("Total").bold(), format!("{passed}/{}", results.len()));
+}
+
+enum RunMode<'a> {
+    // Prompt the user about the exercise and record it when it passes
+    Interactive(&'a mut ProgressState),
     NonInteractive,
 }
 
@@ -54,7 +115,7 @@ pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_only(exercise: &Exercise, run_mode: RunMode, success_hints: bool) -> Result<bool, ()> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
@@ -62,11 +123,15 @@ fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
     let _ = compile(exercise, &progress_bar)?;
     progress_bar.finish_and_clear();
 
-    Ok(prompt_for_completion(exercise, None, success_hints))
+    Ok(prompt_for_completion(exercise, run_mode, None, success_hints))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_run_interactively(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    success_hints: bool,
+) -> Result<bool, ()> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
@@ -80,14 +145,18 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
     let output = match result {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!("Ran {} with errors", exercise);
+            }
             println!("{}", output.stdout);
             println!("{}", output.stderr);
             return Err(());
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    Ok(prompt_for_completion(exercise, run_mode, Some(output.stdout), success_hints))
 }
 
 // Compile the given Exercise as a test harness and display
@@ -106,18 +175,19 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
             if verbose {
                 println!("{}", output.stdout);
             }
-            if let RunMode::Interactive = run_mode {
-                Ok(prompt_for_completion(exercise, None, success_hints))
-            } else {
-                Ok(true)
-            }
+            Ok(prompt_for_completion(exercise, run_mode, None, success_hints))
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            if output.timed_out {
+                warn_timed_out(exercise);
+            } else {
+                warn!(
+                    "Testing of {} failed! Please try again. Here's the output:",
+                    exercise
+                );
+            }
             println!("{}", output.stdout);
+            print_test_results(&output);
             Err(())
         }
     }
@@ -135,26 +205,77 @@ fn compile<'a, 'b>(
         Ok(compilation) => Ok(compilation),
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stderr);
+            if output.timed_out {
+                warn_timed_out(exercise);
+                println!("{}", output.stdout);
+            } else {
+                warn!(
+                    "Compiling of {} failed! Please try again. Here's the output:",
+                    exercise
+                );
+                println!("{}", output.stderr);
+                // Build script exercises are tested while compiling them
+                print_test_results(&output);
+            }
             Err(())
         }
     }
 }
 
-fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
-    let context = match exercise.state() {
-        State::Done => return true,
-        State::Pending(context) => context,
+// Sum up how many tests pass and name the failing ones,
+// which are easy to miss in the middle of the test output
+fn print_test_results(output: &ExerciseOutput) {
+    let results = output.test_results();
+    if results.failed.is_empty() {
+        return;
+    }
+    println!("{}", style(&results).bold());
+    for name in &results.failed {
+        println!("  {} {name}", style("✗").red());
+    }
+    println!();
+}
+
+// Tell the user that the exercise was killed, which usually means it loops forever
+pub fn warn_timed_out(exercise: &Exercise) {
+    let message = format!(
+        "{exercise} timed out after {} seconds! Is there an infinite loop?",
+        exercise.limits().timeout.as_secs()
+    );
+    warn!("{}", message);
+}
+
+// Tell the user that the exercise passed. Returns whether to move on to the next one,
+// which in the legacy marker mode requires removing the `I AM NOT DONE` comment.
+fn prompt_for_completion(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    prompt_output: Option<String>,
+    success_hints: bool,
+) -> bool {
+    let progress_state = match run_mode {
+        RunMode::Interactive(progress_state) => progress_state,
+        RunMode::NonInteractive => return true,
+    };
+    let already_done = progress_state.has_passed(exercise);
+    progress_state.record_pass(exercise);
+
+    let context = if progress_state.uses_legacy_marker() {
+        match exercise.state() {
+            State::Done => return true,
+            State::Pending(context) => Some(context),
+        }
+    } else if already_done {
+        return true;
+    } else {
+        None
     };
     match exercise.mode {
         Mode::Compile => success!("Successfully ran {}!", exercise),
         Mode::Test => success!("Successfully tested {}!", exercise),
         Mode::Clippy => success!("Successfully compiled {}!", exercise),
         Mode::BuildScript => success!("Successfully compiled {}!", exercise),
+        Mode::Cargo => success!("Successfully tested {}!", exercise),
     }
 
     let no_emoji = env::var("NO_EMOJI").is_ok();
@@ -170,6 +291,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
         Mode::Test => "The code is compiling, and the tests pass!",
         Mode::Clippy => clippy_success_msg,
         Mode::BuildScript => "Build script works!",
+        Mode::Cargo => "The code is compiling, and the tests pass!",
     };
     println!();
     if no_emoji {
@@ -189,11 +311,16 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, suc
     if success_hints {
         println!("Hints:");
         println!("{}", separator());
-        println!("{}", exercise.hint);
+        println!("{}", exercise.full_hint());
         println!("{}", separator());
         println!();
     }
 
+    let context = match context {
+        Some(context) => context,
+        None => return true,
+    };
+
     println!("You can keep working on this exercise,");
     println!(
         "or jump into the next one by removing the {} comment:",
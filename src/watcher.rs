@@ -0,0 +1,239 @@
+use crate::exercise::Exercise;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::event::{AccessKind, AccessMode, MetadataKind, ModifyKind};
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Files that change while exercises are built or edited but aren't edits of
+// the exercises themselves: cargo's build output, the binaries rustlings
+// compiles exercises into and the swap and backup files of editors.
+const DEFAULT_IGNORE: &[&str] = &[
+    "target/",
+    "Cargo.lock",
+    "temp_*",
+    "*~",
+    ".*.sw?",
+    ".#*",
+    "#*#",
+    "4913",
+    "*.tmp",
+    "*.bak",
+];
+
+// How often the polling watcher looks for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// The files edited since the last batch. Errors of the watcher are passed on.
+pub type Events = Receiver<notify::Result<Vec<PathBuf>>>;
+
+pub struct WatchOptions {
+    // How long to wait after the last edit before reporting a batch
+    pub debounce: Duration,
+    // Gitignore style patterns of files whose edits are not reported,
+    // in addition to the default ones
+    pub ignore: Vec<String>,
+    // Poll for changes even if the platform can report them
+    pub poll: bool,
+}
+
+// Watches the files for as long as it is alive
+pub struct FileWatcher {
+    _watcher: Box<dyn Watcher>,
+}
+
+// Watch the exercises, and the files outside of `exercises` they depend on.
+// Falls back to polling when the platform's file watching can't be set up,
+// which happens on some network drives and in containers.
+pub fn watch(exercises: &[Exercise], options: &WatchOptions) -> notify::Result<(FileWatcher, Events)> {
+    let root = env::current_dir()?;
+    let ignore = ignore_rules(&root, &options.ignore)?;
+    let paths = watched_paths(&root, exercises);
+
+    let (raw_tx, raw_rx) = channel();
+    let native = if options.poll {
+        None
+    } else {
+        match start::<RecommendedWatcher>(raw_tx.clone(), Config::default(), &paths) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Can't watch the exercises for changes ({}), polling for them instead", e);
+                None
+            }
+        }
+    };
+    let watcher = match native {
+        Some(watcher) => watcher,
+        None => {
+            let config = Config::default().with_poll_interval(POLL_INTERVAL);
+            start::<PollWatcher>(raw_tx, config, &paths)?
+        }
+    };
+
+    let (tx, rx) = channel();
+    let delay = options.debounce;
+    thread::spawn(move || debounce(&raw_rx, &tx, &root, &ignore, delay));
+    Ok((FileWatcher { _watcher: watcher }, rx))
+}
+
+// The exercises directory, plus the files outside of it that exercises watch
+// or take their hidden tests from
+fn watched_paths(root: &Path, exercises: &[Exercise]) -> Vec<(PathBuf, RecursiveMode)> {
+    let mut paths = vec![(root.join("exercises"), RecursiveMode::Recursive)];
+    for file in exercises.iter().flat_map(|e| e.watch.iter().chain(&e.hidden_tests)) {
+        let path = (root.join(file), RecursiveMode::NonRecursive);
+        if !file.starts_with("exercises") && file.exists() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+fn start<W: Watcher + 'static>(
+    tx: Sender<notify::Result<Event>>,
+    config: Config,
+    paths: &[(PathBuf, RecursiveMode)],
+) -> notify::Result<Box<dyn Watcher>> {
+    let mut watcher = W::new(tx, config)?;
+    for (path, mode) in paths {
+        watcher.watch(path, *mode)?;
+    }
+    Ok(Box::new(watcher))
+}
+
+fn ignore_rules(root: &Path, patterns: &[String]) -> notify::Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    let patterns = DEFAULT_IGNORE.iter().copied().chain(patterns.iter().map(String::as_str));
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| notify::Error::generic(&format!("invalid ignore pattern `{pattern}`: {e}")))?;
+    }
+    builder
+        .build()
+        .map_err(|e| notify::Error::generic(&e.to_string()))
+}
+
+fn is_ignored(ignore: &Gitignore, root: &Path, path: &Path) -> bool {
+    path.starts_with(root) && ignore.matched_path_or_any_parents(path, path.is_dir()).is_ignore()
+}
+
+// Whether the event can mean that the contents of a file changed. Files being
+// opened and read are reported too, which happens whenever an exercise is built.
+fn is_edit(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Any)
+            | EventKind::Modify(ModifyKind::Data(_))
+            | EventKind::Modify(ModifyKind::Name(_))
+            | EventKind::Modify(ModifyKind::Metadata(MetadataKind::WriteTime))
+            | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    )
+}
+
+// Collect the edited files until none was edited for `delay`, then send them
+// all at once. Runs until either side of the channels hangs up.
+fn debounce(
+    raw: &Receiver<notify::Result<Event>>,
+    tx: &Sender<notify::Result<Vec<PathBuf>>>,
+    root: &Path,
+    ignore: &Gitignore,
+    delay: Duration,
+) {
+    let mut edited: Vec<PathBuf> = vec![];
+    let mut last_edit = Instant::now();
+    loop {
+        let event = if edited.is_empty() {
+            raw.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            raw.recv_timeout((last_edit + delay).saturating_duration_since(Instant::now()))
+        };
+        let sent = match event {
+            Ok(Ok(event)) => {
+                if is_edit(&event.kind) {
+                    for path in event.paths {
+                        if !is_ignored(ignore, root, &path) {
+                            last_edit = Instant::now();
+                            if !edited.contains(&path) {
+                                edited.push(path);
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Ok(Err(e)) => tx.send(Err(e)),
+            Err(RecvTimeoutError::Timeout) => tx.send(Ok(std::mem::take(&mut edited))),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_watched_paths() {
+        let root = env::current_dir().unwrap();
+        let hidden_tests = PathBuf::from("tests/fixture/hidden_tests/hidden_tests/sum.rs");
+        let mut sum = Exercise::new("sum", "tests/fixture/hidden_tests/sum.rs", Mode::Test);
+        sum.hidden_tests = Some(hidden_tests.clone());
+        sum.watch = vec![hidden_tests.clone(), "tests/fixture/missing.rs".into()];
+        assert_eq!(
+            watched_paths(&root, &[sum]),
+            [
+                (root.join("exercises"), RecursiveMode::Recursive),
+                (root.join(hidden_tests), RecursiveMode::NonRecursive),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let root = Path::new("/home/me/rustlings");
+        let ignore = ignore_rules(root, &["*.txt".into(), "!temp_keep".into()]).unwrap();
+        let ignored = |path: &str| is_ignored(&ignore, root, &root.join(path));
+        assert!(ignored("exercises/clippy/target/debug/clippy1"));
+        assert!(ignored("exercises/tests/Cargo.lock"));
+        assert!(ignored("temp_1234_5678"));
+        assert!(ignored("exercises/intro/.intro1.rs.swp"));
+        assert!(ignored("exercises/intro/intro1.rs~"));
+        assert!(ignored("exercises/intro/notes.txt"));
+        assert!(!ignored("temp_keep"));
+        assert!(!ignored("exercises/intro/intro1.rs"));
+        assert!(!ignored("exercises/tests/build.rs"));
+        assert!(!is_ignored(&ignore, root, Path::new("/elsewhere/target/x")));
+    }
+
+    #[test]
+    fn test_debounce() {
+        let (raw_tx, raw_rx) = channel();
+        let (tx, rx) = channel();
+        let root = PathBuf::from("/home/me/rustlings");
+        let ignore = ignore_rules(&root, &[]).unwrap();
+        let delay = Duration::from_millis(50);
+        let edit = |kind, path: &str| Ok(Event::new(kind).add_path(root.join(path)));
+        let write = EventKind::Access(AccessKind::Close(AccessMode::Write));
+        let open = EventKind::Access(AccessKind::Open(AccessMode::Any));
+        raw_tx.send(edit(open, "exercises/a.rs")).unwrap();
+        raw_tx.send(edit(write, "exercises/b.rs")).unwrap();
+        raw_tx.send(edit(write, "exercises/target/c")).unwrap();
+        raw_tx.send(edit(write, "exercises/b.rs")).unwrap();
+        let debouncer = thread::spawn(move || debounce(&raw_rx, &tx, &root, &ignore, delay));
+
+        let batch = rx.recv().unwrap().unwrap();
+        assert_eq!(batch, [PathBuf::from("/home/me/rustlings/exercises/b.rs")]);
+        drop(raw_tx);
+        debouncer.join().unwrap();
+        assert!(rx.recv().is_err());
+    }
+}
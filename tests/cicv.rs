@@ -1,12 +1,20 @@
 use assert_cmd::prelude::*;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
+// The CI workflow hands the report written here to the autograder
+const OUTPUT: &str = ".github/result/check_result.json";
+
 #[test]
 fn cicvverify() {
+    // Never grade a stale or hand-edited report
+    let _ = fs::remove_file(OUTPUT);
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "cicvverify"]) 
+        .args(&["--nocapture", "cicvverify"])
         // .current_dir("exercises")
         .assert()
         .success();
+    assert!(Path::new(OUTPUT).exists());
 }
@@ -0,0 +1,5 @@
+#[test]
+fn parses_json() {
+    let value: serde_json::Value = serde_json::from_str(r#"{"answer": 42}"#).unwrap();
+    assert_eq!(value["answer"], 43);
+}
@@ -0,0 +1,5 @@
+// I AM NOT DONE
+
+pub fn answer() -> i32 {
+    42
+}
@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn adds() {
+    assert_eq!(sum(1, 2), 3);
+}
@@ -0,0 +1,5 @@
+// An exercise that never finishes, used to test timeouts
+
+fn main() {
+    loop {}
+}
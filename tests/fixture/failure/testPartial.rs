@@ -0,0 +1,13 @@
+#[test]
+fn first() {}
+
+#[test]
+fn second() {}
+
+#[test]
+fn third() {}
+
+#[test]
+fn fourth() {
+    assert_eq!(1 + 1, 3);
+}
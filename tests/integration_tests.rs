@@ -1,9 +1,12 @@
 use assert_cmd::prelude::*;
 use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[test]
 fn runs_without_arguments() {
@@ -22,72 +25,99 @@ fn fails_when_in_wrong_dir() {
 
 #[test]
 fn verify_all_success() {
+    let dir = fixture_copy("success", "verify_all_success");
     Command::cargo_bin("rustlings")
         .unwrap()
         .arg("verify")
-        .current_dir("tests/fixture/success")
+        .current_dir(&dir)
         .assert()
         .success();
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn verify_fails_if_some_fails() {
+    let dir = fixture_copy("failure", "verify_fails_if_some_fails");
     Command::cargo_bin("rustlings")
         .unwrap()
         .arg("verify")
-        .current_dir("tests/fixture/failure")
+        .current_dir(&dir)
         .assert()
         .code(1);
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_compile_success() {
+    let dir = fixture_copy("success", "run_single_compile_success");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "compSuccess"])
-        .current_dir("tests/fixture/success/")
+        .current_dir(&dir)
         .assert()
         .success();
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_compile_failure() {
+    let dir = fixture_copy("failure", "run_single_compile_failure");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "compFailure"])
-        .current_dir("tests/fixture/failure/")
+        .current_dir(&dir)
         .assert()
         .code(1);
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_test_success() {
+    let dir = fixture_copy("success", "run_single_test_success");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .current_dir(&dir)
         .assert()
         .success();
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_test_failure() {
+    let dir = fixture_copy("failure", "run_single_test_failure");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "testFailure"])
-        .current_dir("tests/fixture/failure/")
+        .current_dir(&dir)
         .assert()
         .code(1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_single_compile_timeout() {
+    let dir = fixture_copy("failure", "run_single_compile_timeout");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["run", "infiniteLoop"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out after 1 seconds"));
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_test_not_passed() {
+    let dir = fixture_copy("failure", "run_single_test_not_passed");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "testNotPassed.rs"])
-        .current_dir("tests/fixture/failure/")
+        .current_dir(&dir)
         .assert()
         .code(1);
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
@@ -102,12 +132,14 @@ fn run_single_test_no_filename() {
 
 #[test]
 fn run_single_test_no_exercise() {
+    let dir = fixture_copy("failure", "run_single_test_no_exercise");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "compNoExercise.rs"])
-        .current_dir("tests/fixture/failure")
+        .current_dir(&dir)
         .assert()
         .code(1);
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
@@ -126,20 +158,213 @@ fn reset_no_exercise() {
         .arg("reset")
         .assert()
         .code(1)
-        .stderr(predicates::str::contains(
-            "positional arguments not provided",
-        ));
+        .stdout(predicates::str::contains("Tell me what to reset"));
 }
 
 #[test]
 fn get_hint_for_single_test() {
+    let dir = fixture_copy("failure", "get_hint_for_single_test");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["hint", "testFailure"])
-        .current_dir("tests/fixture/failure")
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout("Hello!\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn get_hint_by_prefix_or_path() {
+    let dir = fixture_copy("failure", "get_hint_by_prefix_or_path");
+    for name in ["testF", "./testFailure.rs"] {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["hint", name])
+            .current_dir(&dir)
+            .assert()
+            .code(0)
+            .stdout("Hello!\n");
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "testFialure"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout("No exercise found for 'testFialure'! Did you mean `testFailure`?\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn get_hints_one_level_at_a_time() {
+    let dir = fixture_copy("failure", "hint_levels");
+    let info = fs::read_to_string(dir.join("info.toml")).unwrap();
+    let info = info.replace("hint = \"Hello!\"", "hint = [\"Hello!\", \"Look at the test.\"]");
+    fs::write(dir.join("info.toml"), info).unwrap();
+    let hint = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["hint", "testFailure"])
+            .current_dir(&dir)
+            .assert()
+            .code(0)
+    };
+    hint().stdout("Hint 1/2: Hello!\n\n1 more hint left, ask for a hint again to see the next one.\n");
+    hint().stdout("Hint 1/2: Hello!\n\nHint 2/2: Look at the test.\n");
+    hint().stdout("Hint 1/2: Hello!\n\nHint 2/2: Look at the test.\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--hints", "--output", "results.json"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let results = fs::read_to_string(dir.join("results.json")).unwrap();
+    assert!(results.contains("\"hints_used\": 2"));
+    assert!(results.contains("\"hints_used\": 0"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn get_hint_with_metadata() {
+    let dir = fixture_copy("success", "get_hint_with_metadata");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["hint", "testSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout(
+            predicates::str::contains("builds on: compSuccess")
+                .and(predicates::str::contains("ch11-01-writing-tests.html")),
+        );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_info_reports_all_problems() {
+    let dir = fixture_copy("check_info/broken", "check_info_reports_all_problems");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-info")
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Found 13 problems")
+                .and(predicates::str::contains("valid: listed more than once"))
+                .and(predicates::str::contains("same_path: has the same path as `valid`"))
+                .and(predicates::str::contains("missing: can't read"))
+                .and(predicates::str::contains("no_marker: missing the `I AM NOT DONE` comment"))
+                .and(predicates::str::contains("no_tests: has mode \"test\" but contains no tests"))
+                .and(predicates::str::contains("exercises/topic/unlisted.rs: not listed"))
+                .and(predicates::str::contains("no_tests: unknown prerequisite `later`"))
+                .and(predicates::str::contains(
+                    "no_tests: watches exercises/topic/data.txt, which doesn't exist",
+                ))
+                .and(predicates::str::contains(
+                    "same_path: only exercises with mode \"test\" or \"cargo\" can have hidden tests",
+                ))
+                .and(predicates::str::contains(
+                    "same_path: the hidden tests file hidden_tests/same_path.rs doesn't exist",
+                ))
+                .and(predicates::str::contains("no_marker: passes without being solved"))
+                .and(predicates::str::contains("valid: the solution solutions/valid.rs doesn't pass")),
+        );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_info_without_problems() {
+    let dir = fixture_copy("check_info/clean", "check_info_without_problems");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-info")
+        .current_dir(&dir)
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("no problems found"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_all_tracks() {
+    let dir = fixture_copy("tracks", "run_rustlings_list_all_tracks");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "compSuccess\nextra/compSuccess\nextra/testSuccess\nProgress",
+        ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_single_track() {
+    let dir = fixture_copy("tracks", "run_rustlings_list_single_track");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--track", "extra", "list", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "extra/compSuccess\nextra/testSuccess\nProgress",
+        ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_unknown_track() {
+    let dir = fixture_copy("tracks", "run_rustlings_unknown_track");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--track", "missing", "list"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("There is no track named `missing`"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_single_exercise_of_track() {
+    let dir = fixture_copy("tracks", "run_single_exercise_of_track");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "extra/testSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_cargo_exercises() {
+    let dir = fixture_copy("cargo", "run_cargo_exercises");
+    // Both exercises are members of the same generated workspace, so they
+    // are run one after the other. The dependencies come from the crates
+    // that were downloaded to build rustlings itself.
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "cargoSuccess"])
+        .env("CARGO_NET_OFFLINE", "true")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "cargoFailure"])
+        .env("CARGO_NET_OFFLINE", "true")
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("test result: FAILED"));
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
@@ -169,98 +394,496 @@ fn all_exercises_require_confirmation() {
 
 #[test]
 fn run_compile_exercise_does_not_prompt() {
+    let dir = fixture_copy("state", "run_compile_exercise_does_not_prompt");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "pending_exercise"])
-        .current_dir("tests/fixture/state")
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("I AM NOT DONE").not());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_test_exercise_does_not_prompt() {
+    let dir = fixture_copy("state", "run_test_exercise_does_not_prompt");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "pending_test_exercise"])
-        .current_dir("tests/fixture/state")
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("I AM NOT DONE").not());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_test_success_with_output() {
+    let dir = fixture_copy("success", "run_single_test_success_with_output");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["--nocapture", "run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("THIS TEST TOO SHALL PASS"));
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_single_test_success_without_output() {
+    let dir = fixture_copy("success", "run_single_test_success_without_output");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["run", "testSuccess"])
-        .current_dir("tests/fixture/success/")
+        .current_dir(&dir)
         .assert()
         .code(0)
         .stdout(predicates::str::contains("THIS TEST TOO SHALL PASS").not());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list() {
+    let dir = fixture_copy("success", "run_rustlings_list");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["list"])
-        .current_dir("tests/fixture/success")
+        .current_dir(&dir)
         .assert()
         .success();
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
-fn run_rustlings_list_no_pending() {
+fn run_rustlings_list_shows_metadata() {
+    let dir = fixture_copy("success", "run_rustlings_list_shows_metadata");
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["list"])
-        .current_dir("tests/fixture/success")
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("testing").and(predicates::str::contains("easy")));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_no_pending() {
+    let dir = fixture_copy("success", "run_rustlings_list_no_pending");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["--legacy-marker", "list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Pending").not());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_both_done_and_pending() {
+    let dir = fixture_copy("state", "run_rustlings_list_both_done_and_pending");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/state")
+        .args(&["--legacy-marker", "list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").and(predicates::str::contains("Pending")));
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_without_pending() {
+    let dir = fixture_copy("state", "run_rustlings_list_without_pending");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
-        .current_dir("tests/fixture/state")
+        .args(&["--legacy-marker", "list", "--solved"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Pending").not());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_formats() {
+    let dir = fixture_copy("state", "run_rustlings_list_formats");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--legacy-marker", "list", "--format", "csv", "--mode", "compile", "--sort", "name"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            "name,path,topic,mode,difficulty,status\n\
+             finished_exercise,finished_exercise.rs,,compile,,Done\n\
+             pending_exercise,pending_exercise.rs,,compile,,Pending\n",
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--legacy-marker", "list", "--format", "json", "--group"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::starts_with("[")
+                .and(predicates::str::contains("\"percentage\": 33.3"))
+                .and(predicates::str::contains("Progress").not()),
+        );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_with_selectors() {
+    let dir = fixture_copy("state", "run_rustlings_list_with_selectors");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--filter", "re:^pending_"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("pending_exercise\npending_test_exercise\nProgress"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--filter", "pending_test_exercise..finished_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("pending_test_exercise\nfinished_exercise\nProgress"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--filter", "missing..finished_exercise"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercise found for 'missing'!"));
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_without_done() {
+    let dir = fixture_copy("state", "run_rustlings_list_without_done");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
-        .current_dir("tests/fixture/state")
+        .args(&["--legacy-marker", "list", "--unsolved"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Copy a fixture to a fresh directory, so that the progress state, build
+// cache and cargo workspace written by rustlings don't leak into other tests.
+fn fixture_copy(fixture: &str, test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustlings_{test}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    copy_dir(&PathBuf::from(format!("tests/fixture/{fixture}")), &dir);
+    dir
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap();
+        if name.to_string_lossy().starts_with('.') || name == "target" {
+            continue;
+        }
+        if path.is_dir() {
+            copy_dir(&path, &to.join(name));
+        } else {
+            fs::copy(&path, to.join(name)).unwrap();
+        }
+    }
+}
+
+#[test]
+fn watch_without_terminal_uses_plain_mode() {
+    let dir = fixture_copy("success", "watch_without_terminal");
+    fs::create_dir(dir.join("exercises")).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("watch")
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("All exercises completed"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_skip_is_remembered() {
+    let dir = fixture_copy("failure", "watch_skip");
+    fs::create_dir(dir.join("exercises")).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--plain"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("skip\nlist\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match("compFailure +Skipped").unwrap());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "--plain"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("list\nquit\n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match("compFailure +Skipped")
+                .unwrap()
+                .and(predicates::str::contains("testFailure.rs failed")),
+        );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_checks_edited_exercises() {
+    let dir = std::env::temp_dir().join(format!("rustlings_watch_edit_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("exercises/target")).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"edit\"\npath = \"exercises/edit.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("exercises/edit.rs"), "fn main() { let }\n").unwrap();
+    for poll in [false, true] {
+        let mut args = vec!["watch", "--plain", "--debounce", "100"];
+        if poll {
+            args.push("--poll");
+        }
+        let mut watch = Command::new(env!("CARGO_BIN_EXE_rustlings"))
+            .args(&args)
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_secs(2));
+        // Build output is ignored, the exercise is still failing
+        fs::write(dir.join("exercises/target/junk.rs"), "").unwrap();
+        thread::sleep(Duration::from_secs(2));
+        assert!(watch.try_wait().unwrap().is_none());
+        fs::write(dir.join("exercises/edit.rs"), "fn main() {}\n").unwrap();
+        let started = Instant::now();
+        while watch.try_wait().unwrap().is_none() {
+            if started.elapsed() > Duration::from_secs(30) {
+                watch.kill().unwrap();
+                panic!("watch mode didn't notice the edit");
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        let output = watch.wait_with_output().unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).contains("All exercises completed"));
+        fs::write(dir.join("exercises/edit.rs"), "fn main() { let }\n").unwrap();
+        let _ = fs::remove_file(dir.join(".rustlings-state.json"));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cicvverify_writes_all_outputs() {
+    let dir = fixture_copy("success", "cicvverify_outputs");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output", "out/nested/results.xml", "--output", "out/summary.md"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    let junit = fs::read_to_string(dir.join("out/nested/results.xml")).unwrap();
+    assert!(junit.contains("<testcase name=\"testSuccess\""));
+    let summary = fs::read_to_string(dir.join("out/summary.md")).unwrap();
+    assert!(summary.contains("**Score: 2.00 / 2**"));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output", "results.txt"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Can't tell which format to write results.txt in"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_keep_going_sums_up_topics() {
+    let dir = fixture_copy("failure", "verify_keep_going");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::is_match(r"(?m)^- +0/3 +compFailure, testFailure, infiniteLoop$")
+                .unwrap()
+                .and(predicates::str::is_match(r"(?m)^Total +0/3$").unwrap()),
+        );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_only_the_selected_exercises() {
+    let dir = fixture_copy("failure", "verify_filter");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going", "--filter", "*Failure"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::is_match(r"(?m)^Total +0/2$")
+                .unwrap()
+                .and(predicates::str::contains("infiniteLoop").not()),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--filter", "nothing*"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercises match `nothing*`."));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_writes_junit_report() {
+    let dir = fixture_copy("failure", "verify_report");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report", "reports/junit.xml"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("compFailure.rs failed: compile failure")
+                .and(predicates::str::contains("testFailure.rs failed")),
+        );
+    let junit = fs::read_to_string(dir.join("reports/junit.xml")).unwrap();
+    assert!(junit.contains("<testsuite name=\"compFailure\""));
+    assert!(junit.contains("<testsuite name=\"infiniteLoop\""));
+    assert!(junit.contains("<failure type=\"compile\""));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_pending_until_verified() {
+    let dir = fixture_copy("success", "list_pending_until_verified");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["list", "--solved"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("compSuccess").not());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["list", "--unsolved"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Pending").not());
+    assert!(dir.join(".rustlings-state.json").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn removing_the_marker_does_not_finish_an_exercise() {
+    let dir = fixture_copy("state", "marker_does_not_finish");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["list", "--solved"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("finished_exercise").not());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_and_undo() {
+    let dir = std::env::temp_dir().join(format!("rustlings_reset_and_undo_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("exercises/intro")).unwrap();
+    fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"intro2\"\npath = \"exercises/intro/intro2.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    let exercise = dir.join("exercises/intro/intro2.rs");
+    fs::write(&exercise, "// my version\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["reset", "intro"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert_ne!(fs::read_to_string(&exercise).unwrap(), "// my version\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["reset", "--undo"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&exercise).unwrap(), "// my version\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cicvverify_grades_with_hidden_tests() {
+    let dir = fixture_copy("hidden_tests", "cicvverify_grades_with_hidden_tests");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output", "summary.md"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("sum执行失败"));
+    let summary = fs::read_to_string(dir.join("summary.md")).unwrap();
+    assert!(summary.contains("failing: rustlings_hidden_tests::adds"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn lsp_check_reports_drift() {
+    let dir = fixture_copy("success", "lsp_check");
+    let lsp = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("lsp")
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+    };
+    lsp(&["--check"])
+        .code(1)
+        .stdout(predicates::str::contains("rust-project.json is out of date"));
+    lsp(&[]).success();
+    let project: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("rust-project.json")).unwrap()).unwrap();
+    assert_eq!(project["crates"][0]["display_name"], "compSuccess");
+    assert_eq!(project["crates"][1]["cfg"], serde_json::json!(["test"]));
+    lsp(&["--check"])
+        .success()
+        .stdout(predicates::str::contains("rust-project.json is up to date"));
+
+    fs::write(dir.join("rust-project.json"), "{}").unwrap();
+    lsp(&["--check"]).code(1);
+    fs::remove_dir_all(&dir).unwrap();
 }